@@ -0,0 +1,67 @@
+//! An egui panel for tuning the camera at runtime, enabled by the `debug_ui`
+//! feature
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+
+use crate::{Config, FastMovement, FpsCam};
+
+/// Adds a debug panel showing the state of every `FpsCam` and letting the
+/// movement and look settings of `Config` be edited live. The key bindings are
+/// shown but can't be edited. This requires `bevy_egui::EguiPlugin` to be
+/// added as well.
+pub struct FpsCamDebugUiPlugin;
+
+impl Plugin for FpsCamDebugUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(debug_ui);
+    }
+}
+
+/// Draws the debug panel
+fn debug_ui(
+    mut egui: ResMut<EguiContext>,
+    mut config: ResMut<Config>,
+    mut fast: ResMut<FastMovement>,
+    q: Query<(Entity, &Transform, &FpsCam)>,
+) {
+    egui::Window::new("FpsCam").show(egui.ctx_mut(), |ui| {
+        ui.heading("Movement");
+        ui.checkbox(&mut config.movement_enabled, "movement enabled");
+        ui.add(egui::Slider::new(&mut config.movespeed, 0.0..=50.0).text("movespeed"));
+        ui.add(egui::Slider::new(&mut config.fast_movespeed, 0.0..=50.0).text("fast movespeed"));
+        ui.checkbox(&mut fast.0, "fast movement");
+        ui.add(egui::Slider::new(&mut config.sprint_multiplier, 1.0..=10.0).text("sprint"));
+        ui.add(egui::Slider::new(&mut config.precision_multiplier, 0.0..=1.0).text("precision"));
+
+        ui.heading("Looking");
+        ui.add(
+            egui::Slider::new(&mut config.sensitivity, 0.0001..=0.01)
+                .logarithmic(true)
+                .text("sensitivity"),
+        );
+        ui.add(
+            egui::Slider::new(&mut config.keyboard_look_speed, 0.0..=10.0)
+                .text("keyboard look speed"),
+        );
+        ui.add(egui::Slider::new(&mut config.look_smoothing, 0.0..=0.5).text("smoothing"));
+
+        ui.collapsing("Key bindings", |ui| {
+            ui.label(format!("{:#?}", config.key_bindings));
+        });
+
+        ui.heading("Cameras");
+        for (entity, transform, fpscam) in q.iter() {
+            let position = transform.translation;
+            ui.label(format!(
+                "{:?}: position ({:.2}, {:.2}, {:.2}), yaw {:.1}°, pitch {:.1}°",
+                entity,
+                position.x,
+                position.y,
+                position.z,
+                fpscam.yaw.to_degrees(),
+                fpscam.pitch.to_degrees(),
+            ));
+        }
+    });
+}
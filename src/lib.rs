@@ -10,7 +10,7 @@
 //!
 //! # Usage
 //! 1. Add to Cargo.toml, matching major/minor with bevy
-//! ```
+//! ```toml
 //! [dependencies]
 //! bevy = "X.Y"
 //! bevy-fpscam = "X.Y"
@@ -25,19 +25,23 @@
 //! add a `FpsCam` component to your camera.
 //!
 //! 3. Add the plugin
-//! ```
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fpscam::*;
 //! fn main() {
 //!     App::new()
 //!         .add_plugins(DefaultPlugins)
 //!         .add_plugin(FpsCamPlugin)
-//!         .run();     
+//!         .run();
 //! }
 //! ```
 //!
 //! # Customization
 //! You can modify mouse sensitivity, movement speed and keybindings
 //! by modifying the resource of type `bevy_fpscam::Config`
-//! ```
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_fpscam::*;
 //! fn main() {
 //!     App::new()
 //!         .add_plugins(DefaultPlugins)
@@ -46,50 +50,314 @@
 //!             movespeed: 2.0,
 //!             sensitivity: 0.01,
 //!             key_bindings: KeyBindings {
-//!                 unlock: Some(KeyCode::Enter),
+//!                 zoom: Some(KeyCode::Z.into()),
 //!                 ..Default::default()
-//!         }}).run();
+//!             },
+//!             ..Default::default()
+//!         })
+//!         .insert_resource(UnlockTriggers(vec![
+//!             UnlockTrigger::Key(KeyCode::Return.into()),
+//!         ]))
+//!         .run();
 //! }
 //! ```
+//!
+//! Setting `UnlockTriggers` to an empty list disables the built-in unlocking,
+//! so the app can handle Escape (e.g. for a pause menu) on its own.
+//!
+//! Enabling the `debug_ui` feature adds `FpsCamDebugUiPlugin`, an egui panel
+//! for tuning `Config` live. It requires `bevy_egui::EguiPlugin` to be added too.
+
+// systems receive every resource and query they access as a parameter, and the
+// queries spell out the components they read, so both lints fire on idiomatic
+// bevy code
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+use std::{collections::HashMap, marker::PhantomData};
+
+#[cfg(feature = "debug_ui")]
+mod debug_ui;
+#[cfg(feature = "debug_ui")]
+pub use debug_ui::FpsCamDebugUiPlugin;
 
 use bevy::{
+    core::FixedTimestep,
+    ecs::schedule::ShouldRun,
     input::{
         keyboard::KeyboardInput,
         mouse::{MouseButtonInput, MouseMotion},
         ElementState,
     },
     prelude::*,
-    window::WindowFocused,
+    render::view::RenderLayers,
+    utils::Instant,
+    window::{WindowFocused, WindowMoved, WindowResized},
 };
 
+/// Modifier keys which have to be held for a `Binding` to trigger. Either the
+/// left or the right key satisfies a modifier.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+}
+
+impl Modifiers {
+    /// Whether all the required modifiers are held, according to `pressed`
+    fn held(&self, pressed: impl Fn(KeyCode) -> bool) -> bool {
+        let either = |required: bool, left: KeyCode, right: KeyCode| {
+            !required || pressed(left) || pressed(right)
+        };
+
+        either(self.shift, KeyCode::LShift, KeyCode::RShift)
+            && either(self.control, KeyCode::LControl, KeyCode::RControl)
+            && either(self.alt, KeyCode::LAlt, KeyCode::RAlt)
+    }
+}
+
+/// A key, optionally combined with modifier keys which have to be held along
+/// with it. Holding more modifiers than required still triggers the binding. A
+/// plain key converts into a binding without modifiers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Binding {
+    pub key: KeyCode,
+    pub modifiers: Modifiers,
+    /// Whether the binding triggers at all. Clearing this disables the action
+    /// while remembering its key, for example in a settings menu.
+    pub enabled: bool,
+}
+
+impl Binding {
+    pub fn new(key: KeyCode, modifiers: Modifiers) -> Self {
+        Self {
+            key,
+            modifiers,
+            enabled: true,
+        }
+    }
+}
+
+impl From<KeyCode> for Binding {
+    fn from(key: KeyCode) -> Self {
+        Self::new(key, Modifiers::default())
+    }
+}
+
 /// Keybindings for controlling the camera. Default is WASD for movement, space
-/// for up, LCTRL for down and ESC for unlocking the cursor. All keybinds are
-/// optional.
+/// for up and LCTRL for down. The keys which unlock the cursor are set with
+/// the `UnlockTriggers` resource. Zooming, keyboard looking, recentering,
+/// sprinting and precision movement are unbound by default. None of the
+/// default bindings require modifiers. All keybinds are optional.
 #[derive(Clone, Copy, Debug)]
 pub struct KeyBindings {
-    pub forward: Option<KeyCode>,
-    pub back: Option<KeyCode>,
-    pub left: Option<KeyCode>,
-    pub right: Option<KeyCode>,
-    pub up: Option<KeyCode>,
-    pub down: Option<KeyCode>,
-    pub unlock: Option<KeyCode>,
+    pub forward: Option<Binding>,
+    pub back: Option<Binding>,
+    pub left: Option<Binding>,
+    pub right: Option<Binding>,
+    pub up: Option<Binding>,
+    pub down: Option<Binding>,
+    pub zoom: Option<Binding>,
+    pub look_up: Option<Binding>,
+    pub look_down: Option<Binding>,
+    pub look_left: Option<Binding>,
+    pub look_right: Option<Binding>,
+    /// Smoothly levels the pitch of the camera, leaving the yaw untouched
+    pub recenter_pitch: Option<Binding>,
+    /// Moves faster while held, see `Config::sprint_multiplier`
+    pub sprint: Option<Binding>,
+    /// Moves slower while held for fine positioning, see
+    /// `Config::precision_multiplier`. This takes precedence over `sprint` if
+    /// both are held.
+    pub precision: Option<Binding>,
+    /// While held, looking left and right turns only the view and not the
+    /// direction of movement. The view returns to the direction of movement
+    /// once released, see `Config::free_look_return_time`.
+    pub free_look: Option<Binding>,
+    /// While held, movement is kept horizontal so that the camera holds its
+    /// altitude, even when looking up or down
+    pub lock_vertical: Option<Binding>,
+    /// Switches between `Config::movespeed` and `Config::fast_movespeed` on
+    /// every press. Unlike `sprint`, this doesn't need to be held.
+    pub fast_toggle: Option<Binding>,
+    /// Lowers the view by `Config::crouch_height` while held
+    pub crouch: Option<Binding>,
+    /// Dashes `Config::dash_distance` forward. Pressing any movement key
+    /// cancels an ongoing dash.
+    pub dash: Option<Binding>,
+    /// Roll the camera while held, only in `Config::six_dof` mode
+    pub roll_left: Option<Binding>,
+    pub roll_right: Option<Binding>,
+    /// Smoothly rotates the camera back to level, removing any roll, over
+    /// `Config::recenter_duration`
+    pub level: Option<Binding>,
+    /// Aims down sights while held, zooming to `Config::ads_fov` and moving
+    /// the view by `Config::ads_offset`
+    pub ads: Option<Binding>,
+    /// Toggles whether mouse motion turns the pitch of the camera, see
+    /// `LookAxisLocks`
+    pub lock_pitch: Option<Binding>,
+    /// Toggles whether mouse motion turns the yaw of the camera, see
+    /// `LookAxisLocks`
+    pub lock_yaw: Option<Binding>,
+    /// Turns the view around by `Config::look_behind_angle` while held, like
+    /// the rear view in a racing game, facing the same way as before once
+    /// released
+    pub look_behind: Option<Binding>,
 }
 
 impl Default for KeyBindings {
     fn default() -> Self {
         Self {
-            forward: Some(KeyCode::W),
-            back: Some(KeyCode::S),
-            left: Some(KeyCode::A),
-            right: Some(KeyCode::D),
-            up: Some(KeyCode::Space),
-            down: Some(KeyCode::LControl),
-            unlock: Some(KeyCode::Escape),
+            forward: Some(KeyCode::W.into()),
+            back: Some(KeyCode::S.into()),
+            left: Some(KeyCode::A.into()),
+            right: Some(KeyCode::D.into()),
+            up: Some(KeyCode::Space.into()),
+            down: Some(KeyCode::LControl.into()),
+            zoom: None,
+            look_up: None,
+            look_down: None,
+            look_left: None,
+            look_right: None,
+            recenter_pitch: None,
+            sprint: None,
+            precision: None,
+            free_look: None,
+            lock_vertical: None,
+            fast_toggle: None,
+            crouch: None,
+            dash: None,
+            roll_left: None,
+            roll_right: None,
+            level: None,
+            ads: None,
+            lock_pitch: None,
+            lock_yaw: None,
+            look_behind: None,
+        }
+    }
+}
+
+/// An action which can be bound in `KeyBindings`, one for each of its fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CameraAction {
+    Forward,
+    Back,
+    Left,
+    Right,
+    Up,
+    Down,
+    Zoom,
+    LookUp,
+    LookDown,
+    LookLeft,
+    LookRight,
+    RecenterPitch,
+    Sprint,
+    Precision,
+    FreeLook,
+    LockVertical,
+    FastToggle,
+    Crouch,
+    Dash,
+    RollLeft,
+    RollRight,
+    Level,
+    Ads,
+    LockPitch,
+    LockYaw,
+    LookBehind,
+}
+
+impl KeyBindings {
+    /// The binding of `action`
+    pub fn binding_mut(&mut self, action: CameraAction) -> &mut Option<Binding> {
+        match action {
+            CameraAction::Forward => &mut self.forward,
+            CameraAction::Back => &mut self.back,
+            CameraAction::Left => &mut self.left,
+            CameraAction::Right => &mut self.right,
+            CameraAction::Up => &mut self.up,
+            CameraAction::Down => &mut self.down,
+            CameraAction::Zoom => &mut self.zoom,
+            CameraAction::LookUp => &mut self.look_up,
+            CameraAction::LookDown => &mut self.look_down,
+            CameraAction::LookLeft => &mut self.look_left,
+            CameraAction::LookRight => &mut self.look_right,
+            CameraAction::RecenterPitch => &mut self.recenter_pitch,
+            CameraAction::Sprint => &mut self.sprint,
+            CameraAction::Precision => &mut self.precision,
+            CameraAction::FreeLook => &mut self.free_look,
+            CameraAction::LockVertical => &mut self.lock_vertical,
+            CameraAction::FastToggle => &mut self.fast_toggle,
+            CameraAction::Crouch => &mut self.crouch,
+            CameraAction::Dash => &mut self.dash,
+            CameraAction::RollLeft => &mut self.roll_left,
+            CameraAction::RollRight => &mut self.roll_right,
+            CameraAction::Level => &mut self.level,
+            CameraAction::Ads => &mut self.ads,
+            CameraAction::LockPitch => &mut self.lock_pitch,
+            CameraAction::LockYaw => &mut self.lock_yaw,
+            CameraAction::LookBehind => &mut self.look_behind,
+        }
+    }
+}
+
+/// Send this event to bind `action` to `key` without modifiers, replacing its
+/// previous binding, for example from a controls menu
+#[derive(Debug, Clone, Copy)]
+pub struct RebindAction {
+    pub action: CameraAction,
+    pub key: KeyCode,
+}
+
+/// How the movement keys move the camera
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MovementMode {
+    /// Move continuously while the keys are held
+    Continuous,
+    /// Move by exactly `step` along the axes of the camera for every key
+    /// press, for example to align the camera to a grid
+    Step { step: f32 },
+}
+
+impl Default for MovementMode {
+    fn default() -> Self {
+        Self::Continuous
+    }
+}
+
+/// Which way the forward movement key moves the camera. Bevy uses a right
+/// handed coordinate system with +X to the right and +Y up, in which cameras
+/// look down -Z, so forward is -Z in the local space of the camera. Assets and
+/// game logic authored for a left handed system expect forward to be +Z with
+/// the same right and up. Rendering is unaffected, the camera always looks
+/// down its local -Z.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Handedness {
+    /// Forward is local -Z, the bevy convention
+    Right,
+    /// Forward is local +Z
+    Left,
+}
+
+impl Handedness {
+    /// The forward direction of `transform` in this convention
+    pub fn forward(self, transform: &Transform) -> Vec3 {
+        match self {
+            Self::Right => transform.forward(),
+            Self::Left => transform.back(),
         }
     }
 }
 
+impl Default for Handedness {
+    fn default() -> Self {
+        Self::Right
+    }
+}
+
 /// Global configuration for the camera. modify the resource of this
 /// type to change from the default configuration
 #[derive(Clone, Copy, Debug)]
@@ -97,6 +365,380 @@ pub struct Config {
     pub movespeed: f32,
     pub sensitivity: f32,
     pub key_bindings: KeyBindings,
+    /// The vertical field of view, in radians, while the zoom key is held
+    pub zoom_fov: f32,
+    /// What the scale of orthographic cameras is multiplied by while the zoom
+    /// key is held
+    pub zoom_scale: f32,
+    /// How long, in seconds, zooming in or out takes
+    pub zoom_time: f32,
+    /// The sensitivity used while zoomed. If `None`, `sensitivity` is used,
+    /// scaled according to `zoom_scales_sensitivity`.
+    pub zoom_sensitivity: Option<f32>,
+    /// Whether the sensitivity is scaled along with the view while zooming,
+    /// so that the cursor moves across the same part of the view. This has no
+    /// effect if `zoom_sensitivity` is set.
+    pub zoom_scales_sensitivity: bool,
+    /// The vertical field of view, in radians, while aiming down sights. The
+    /// transition uses `zoom_time`, and orthographic cameras use `zoom_scale`.
+    pub ads_fov: f32,
+    /// The sensitivity used while aiming down sights. If `None`, the
+    /// sensitivity is chosen the same way as for zooming.
+    pub ads_sensitivity: Option<f32>,
+    /// How far the view is moved while aiming down sights, in the local space
+    /// of the camera, for example to look over the shoulder
+    pub ads_offset: Vec3,
+    /// Whether the movement speed is scaled along with the view while zooming,
+    /// so that zooming in also gives slower, more precise movement
+    pub speed_scales_with_fov: bool,
+    /// Whether the cursor is locked when the app starts
+    pub start_locked: bool,
+    /// Whether the cursor is visible when the app starts
+    pub start_cursor_visible: bool,
+    /// Editor style cursor handling, for embedding the camera in an editor.
+    /// The cursor is only locked while `editor_look_button` is held, so the
+    /// camera only looks and moves while it is, and the cursor is never
+    /// hidden. Clicking doesn't lock the cursor, the `UnlockTriggers` do
+    /// nothing, and the cursor starts unlocked regardless of `start_locked`.
+    pub editor_mode: bool,
+    /// The mouse button which has to be held to control the camera in
+    /// `editor_mode`
+    pub editor_look_button: MouseButton,
+    /// Where the camera spawned by `FpsCamPlugin` starts
+    pub start_position: Vec3,
+    /// The yaw the camera spawned by `FpsCamPlugin` starts with
+    pub start_yaw: f32,
+    /// The pitch the camera spawned by `FpsCamPlugin` starts with
+    pub start_pitch: f32,
+    /// How fast the keyboard look bindings rotate the camera, in radians per
+    /// second
+    pub keyboard_look_speed: f32,
+    /// How fast the right stick of a gamepad rotates a camera with
+    /// `PlayerInput::Gamepad` at full deflection, in radians per second
+    pub gamepad_look_speed: f32,
+    /// The time constant, in seconds, with which mouse motion is smoothed. Set
+    /// to 0 to disable look smoothing.
+    pub look_smoothing: f32,
+    /// While look smoothing or look momentum is enabled, remaining motion
+    /// smaller than this many radians per frame is discarded so the camera
+    /// comes to a full stop
+    pub look_smoothing_epsilon: f32,
+    /// If set, the camera keeps turning after the mouse stops, slowing down at
+    /// this rate per second, for a weighty feel. Higher values stop sooner.
+    pub look_friction: Option<f32>,
+    /// Whether the camera uses wall-clock time instead of the game's `Time`,
+    /// so that it keeps moving normally while the game is paused or slowed
+    /// down, e.g. for a debug camera
+    pub use_real_time: bool,
+    /// The longest timestep, in seconds, that any time based camera behaviour
+    /// uses in a single frame, so that a frame hitch doesn't make the camera
+    /// jump. If `None`, the timestep isn't limited.
+    pub max_delta: Option<f32>,
+    /// What the timestep of smoothing and transitions, such as look smoothing,
+    /// zooming, recentering and `MoveTo`, is multiplied by, to slow them down
+    /// or speed them up for a cinematic effect. Movement distances and turn
+    /// rates are unaffected. This scales whichever time is used according to
+    /// `use_real_time`, after `max_delta` is applied.
+    pub camera_time_scale: f32,
+    /// Whether time stops while the cursor is unlocked, for example while a
+    /// menu is open. The `Time` of bevy has no way to be paused or slowed
+    /// down, so instead this stops all time based camera behaviour and sets
+    /// the `TimePaused` resource, which the time based systems of the game
+    /// have to check to pause along with it. Leave this off when the game
+    /// manages pausing itself.
+    pub pause_time_on_unlock: bool,
+    /// Whether `MouseMotion` is used to look around. Disable this when all
+    /// look motion should come from a custom `MotionSource`.
+    pub use_mouse_motion: bool,
+    /// Whether `MouseMotion` is ignored for a few frames after the window is
+    /// resized or moved, since some platforms report dragging the window as
+    /// mouse motion which flings the camera around
+    pub suppress_look_on_resize: bool,
+    /// Whether the cursor glides to the center of the window over a few frames
+    /// when it is unlocked, rather than staying wherever it was while locked.
+    /// Mouse motion is ignored for a few frames after the cursor is locked
+    /// again, so anything the platform reports for warping the cursor doesn't
+    /// turn the camera.
+    pub recenter_smoothly: bool,
+    /// Whether touches control the camera, for touch screens. Dragging to the
+    /// right of `touch_look_split` looks around like the mouse, and dragging
+    /// to the left of it moves the camera like a virtual joystick centered on
+    /// where the touch started.
+    pub touch_enabled: bool,
+    /// What the distance dragged is multiplied by before being treated as
+    /// mouse motion
+    pub touch_sensitivity: f32,
+    /// Where the screen is split between the joystick on the left and looking
+    /// on the right, as a fraction of the width of the window
+    pub touch_look_split: f32,
+    /// How far, in pixels, touches have to be dragged from where they started
+    /// to move the camera at full speed
+    pub touch_joystick_radius: f32,
+    /// A single switch for motion-sensitive players, matching the reduced
+    /// motion setting of many platforms. When enabled, it overrides the
+    /// individual settings to disable head-bob, camera shake, strafe tilt,
+    /// look momentum and the look, velocity and speed smoothing, and limits
+    /// how fast the look bindings, the gamepad and pitching with the mouse
+    /// can turn the camera. It also overrides the smoothing of an active
+    /// `MovementProfile`.
+    pub reduced_motion: bool,
+    /// How far, in degrees, the camera rolls into the direction of strafing.
+    /// Set to 0 to disable strafe tilt.
+    pub strafe_tilt_degrees: f32,
+    /// Whether the camera bobs up and down while moving
+    pub head_bob_enabled: bool,
+    /// How far the camera bobs, at full movement speed
+    pub head_bob_amplitude: f32,
+    /// How many sideways sways the head-bob makes per second, at full
+    /// movement speed
+    pub head_bob_frequency: f32,
+    /// How much `CameraShake::trauma` decays per second
+    pub shake_decay: f32,
+    /// The largest rotation, in radians, around each axis at full trauma
+    pub shake_max_angle: f32,
+    /// The largest positional offset along each axis at full trauma
+    pub shake_max_offset: f32,
+    /// The seed for randomized effects such as camera shake. The effects are
+    /// fully deterministic, so the same seed reproduces the same motion.
+    pub effect_seed: u32,
+    /// How long, in seconds, it takes to level the pitch when recentering
+    pub recenter_duration: f32,
+    /// How long, in seconds, a camera which becomes active takes to move from
+    /// the pose of the previously active camera to its own. Input is
+    /// suppressed during the transition. Set to 0 to cut instantly.
+    pub switch_duration: f32,
+    /// Whether all cameras respond to input while none is marked with
+    /// `ActiveFpsCam`, so that they all move together. If disabled, the first
+    /// spawned `FpsCam` is marked with `ActiveFpsCam` whenever no camera is, so
+    /// only one camera is ever controlled at a time.
+    pub control_all_cameras: bool,
+    /// The speed above which `HighSpeed` events are sent. If `None`, they
+    /// aren't sent at all.
+    pub high_speed_threshold: Option<f32>,
+    /// How far the view is lowered while crouching
+    pub crouch_height: f32,
+    /// How long, in seconds, it takes to crouch or stand up
+    pub crouch_transition_time: f32,
+    /// Maps the progress of the crouch transition, from 0 to 1, to how far the
+    /// view is lowered, from 0 to 1. The default is smoothstep.
+    pub crouch_easing: fn(f32) -> f32,
+    /// How far a dash moves the camera towards the crosshair
+    pub dash_distance: f32,
+    /// How long, in seconds, a dash takes
+    pub dash_duration: f32,
+    /// The minimum and maximum corners of the box the camera is confined to
+    pub bounds: Option<(Vec3, Vec3)>,
+    /// The minimum and maximum corners of a box the camera wraps around in,
+    /// like a torus, for tiled scenes which should appear infinite. Moving out
+    /// of one side places the camera on the opposite side, keeping its
+    /// rotation. The jump is not included in `FpsCamMoved` or the velocity of
+    /// the camera. Axes where the minimum isn't below the maximum don't wrap.
+    pub wrap_bounds: Option<(Vec3, Vec3)>,
+    /// The fastest the pitch can change from mouse motion, in radians per
+    /// second. Yaw is not limited by this.
+    pub max_pitch_speed: Option<f32>,
+    /// The minimum and maximum yaw, in radians, to limit the camera to an arc
+    /// like a mounted turret. If the minimum is above the maximum, the arc
+    /// crosses the back of the camera, going from the minimum up to π and on
+    /// from -π up to the maximum, so `(3.0, -3.0)` is a narrow arc facing +Z.
+    /// Such limits are expected within [-π, π]. If `None`, the camera can turn
+    /// all the way around.
+    pub yaw_limits: Option<(f32, f32)>,
+    /// The lowest pitch, in radians, the camera can look down to. The limits
+    /// may be asymmetric, for example to let a camera close to the ground look
+    /// far up but only slightly down, and are kept within straight down and
+    /// straight up. If `pitch_min` is above `pitch_max`, the pitch is held
+    /// halfway between them.
+    pub pitch_min: f32,
+    /// The highest pitch, in radians, the camera can look up to
+    pub pitch_max: f32,
+    /// If set, the movement speed is scaled by `speed_distance_scale` of the
+    /// distance to this point, to move slower when inspecting it up close
+    pub focus_point: Option<Vec3>,
+    /// Maps the distance to `focus_point` to a multiplier for the movement
+    /// speed. The default is the distance itself.
+    pub speed_distance_scale: fn(f32) -> f32,
+    /// The lowest the movement speed can get after all scaling, so that the
+    /// camera never gets stuck when close to `focus_point` or zoomed in
+    pub min_movespeed: f32,
+    /// The time constant, in seconds, with which the movement speed changes
+    /// when switching between speeds, e.g. when starting to sprint, so that
+    /// it never snaps. Set to 0 to switch instantly.
+    pub speed_smoothing: f32,
+    /// The time constant, in seconds, with which the camera accelerates and
+    /// decelerates when movement keys are pressed and released. Set to 0 to
+    /// start and stop instantly.
+    pub velocity_smoothing: f32,
+    /// Whether the camera coasts to a stop when all movement keys are released
+    /// while `velocity_smoothing` is enabled. If disabled, it stops
+    /// immediately, while still accelerating smoothly.
+    pub coast: bool,
+    /// Whether the cameras stop when the window loses focus. The window doesn't
+    /// receive the release of keys held while it is in the background, so
+    /// without this the held keys get stuck and the camera keeps moving once
+    /// the window is focused again. This releases all held keys in
+    /// `Input<KeyCode>` and discards any remaining velocity and look motion.
+    pub stop_on_focus_loss: bool,
+    /// Whether the yaw and pitch are re-derived from the transform every
+    /// frame, so that the camera can be rotated by other systems, e.g.
+    /// physics, without snapping back on the next mouse movement. This costs
+    /// a decomposition of the rotation per camera per frame.
+    pub sync_from_transform: bool,
+    /// Whether the rotation is recomposed from the yaw and pitch every frame,
+    /// so that the camera can be animated by writing `FpsCam::yaw` and
+    /// `FpsCam::pitch` directly. This overwrites rotations set by other
+    /// systems, and is the opposite of `sync_from_transform`.
+    pub apply_yaw_pitch: bool,
+    /// The movement speed used instead of `movespeed` while `FastMovement` is
+    /// toggled on. Sprinting and precision movement are applied on top of it.
+    pub fast_movespeed: f32,
+    /// The speed the up and down keys move at, independently of the speed of
+    /// the other movement keys. Sprinting and precision movement scale it the
+    /// same way as `movespeed`, but nothing else which changes the speed does,
+    /// such as `FastMovement`, `focus_point` or `min_movespeed`. If `None`, all
+    /// movement uses `movespeed`.
+    pub vertical_movespeed: Option<f32>,
+    /// What the movement speed is multiplied by while sprinting
+    pub sprint_multiplier: f32,
+    /// What the movement speed is multiplied by while the precision key is
+    /// held
+    pub precision_multiplier: f32,
+    /// Whether the camera can be moved. Disable this for a camera which can
+    /// only look around, like a turret. This can be toggled at runtime.
+    pub movement_enabled: bool,
+    pub movement_mode: MovementMode,
+    pub handedness: Handedness,
+    /// If set, forward movement follows this world direction, and sideways
+    /// movement is perpendicular to it, regardless of where the camera looks.
+    /// This is meant for on-rails segments.
+    pub rail_direction: Option<Vec3>,
+    /// Motion events smaller than this are ignored, to reduce jitter from noisy
+    /// input devices such as trackpads
+    pub motion_deadzone: f32,
+    /// Whether mouse motion is scaled by the height of the primary window
+    /// relative to a 1080 pixel tall window, so that looking feels the same on
+    /// displays with different resolutions
+    pub dpi_independent: bool,
+    /// Maps how long, in seconds, the mouse has been moving without stopping to
+    /// a multiplier for the sensitivity. Ramping this up gives precision at the
+    /// start of a motion and speed for large turns. The default is always 1.
+    pub sensitivity_ramp: fn(f32) -> f32,
+    /// How fast, in radians per second, roll introduced by other systems is
+    /// rotated back to level. Set to 0 to disable auto-levelling. It doesn't
+    /// fight the roll bindings while they are held, but in `six_dof` mode the
+    /// roll they introduced is levelled out again once they are released.
+    pub auto_level_speed: f32,
+    /// Whether looking turns the camera relative to its current orientation
+    /// rather than around the world up, for six degrees of freedom navigation.
+    /// The pitch isn't clamped, the camera can be rolled with the roll
+    /// bindings, and moving up and down follows the up of the camera. The yaw
+    /// and pitch of the `FpsCam` are kept up to date, but lose any roll.
+    pub six_dof: bool,
+    /// How fast the roll bindings roll the camera, in radians per second
+    pub roll_speed: f32,
+    /// If set, up is the direction away from this point rather than +Y, like
+    /// standing on a planet. Vertical movement, pitch clamping and yaw are then
+    /// relative to this direction, which follows the camera as it moves. The
+    /// view is carried along without turning, so the yaw of the `FpsCam` is
+    /// only meaningful relative to its current position.
+    pub gravity_source: Option<Vec3>,
+    /// How long, in seconds, it takes for the view to return to the direction
+    /// of movement once the free look key is released
+    pub free_look_return_time: f32,
+    /// How far, in radians, the view is turned while the look behind key is
+    /// held
+    pub look_behind_angle: f32,
+    /// The time constant, in seconds, with which the view recovers from a
+    /// `RecoilKick`
+    pub recoil_recovery_time: f32,
+}
+
+impl Config {
+    /// The configuration applied by `SpectatorCamPlugin`. Starting from the
+    /// defaults, this enables `six_dof`, binds Q and E to rolling left and
+    /// right, and R to levelling the camera.
+    pub fn spectator() -> Self {
+        Self {
+            six_dof: true,
+            key_bindings: KeyBindings {
+                roll_left: Some(KeyCode::Q.into()),
+                roll_right: Some(KeyCode::E.into()),
+                level: Some(KeyCode::R.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// The configuration for a camera close to the ground, which can look far
+    /// up but only slightly down. Starting from the defaults, this limits the
+    /// pitch to between -0.2 and 1.4 radians.
+    pub fn ground() -> Self {
+        Self {
+            pitch_min: -0.2,
+            pitch_max: 1.4,
+            start_pitch: 0.0,
+            ..Default::default()
+        }
+    }
+
+    /// The default configuration, with a sensitivity of `degrees` per count
+    /// of mouse motion, the unit many games use for their sensitivity
+    pub fn from_degrees_per_count(degrees: f32) -> Self {
+        Self {
+            sensitivity: degrees.to_radians(),
+            ..Default::default()
+        }
+    }
+
+    /// The default configuration, with a sensitivity where moving a mouse with
+    /// the given `dpi` by `cm` centimeters turns the camera all the way around.
+    ///
+    /// `sensitivity` is the angle in radians the camera turns per count of
+    /// mouse motion, and the mouse reports `dpi / 2.54` counts per
+    /// centimeter, so `sensitivity = 2π / (cm * dpi / 2.54)`. This doesn't
+    /// hold with `dpi_independent` enabled, which scales the motion by the
+    /// size of the window.
+    pub fn from_cm_per_360(cm: f32, dpi: f32) -> Self {
+        Self {
+            sensitivity: std::f32::consts::TAU / (cm * dpi / CM_PER_INCH),
+            ..Default::default()
+        }
+    }
+
+    /// How many centimeters a mouse with the given `dpi` has to move to turn
+    /// the camera all the way around, see `from_cm_per_360`
+    pub fn cm_per_360(&self, dpi: f32) -> f32 {
+        std::f32::consts::TAU / self.sensitivity / dpi * CM_PER_INCH
+    }
+
+    /// This configuration with the overrides of `reduced_motion` applied, if
+    /// enabled
+    fn with_reduced_motion(&self) -> Self {
+        if !self.reduced_motion {
+            return *self;
+        }
+
+        Self {
+            head_bob_enabled: false,
+            shake_max_angle: 0.0,
+            shake_max_offset: 0.0,
+            strafe_tilt_degrees: 0.0,
+            look_friction: None,
+            look_smoothing: 0.0,
+            velocity_smoothing: 0.0,
+            speed_smoothing: 0.0,
+            keyboard_look_speed: self.keyboard_look_speed.min(REDUCED_MOTION_TURN_SPEED),
+            gamepad_look_speed: self.gamepad_look_speed.min(REDUCED_MOTION_TURN_SPEED),
+            max_pitch_speed: Some(
+                self.max_pitch_speed
+                    .map_or(REDUCED_MOTION_TURN_SPEED, |speed| {
+                        speed.min(REDUCED_MOTION_TURN_SPEED)
+                    }),
+            ),
+            ..*self
+        }
+    }
 }
 
 impl Default for Config {
@@ -105,154 +747,5398 @@ impl Default for Config {
             movespeed: 1.0,
             sensitivity: 0.001,
             key_bindings: Default::default(),
+            zoom_fov: std::f32::consts::PI / 12.0,
+            zoom_scale: 0.25,
+            zoom_time: 0.15,
+            zoom_sensitivity: None,
+            zoom_scales_sensitivity: false,
+            ads_fov: std::f32::consts::PI / 6.0,
+            ads_sensitivity: None,
+            ads_offset: Vec3::ZERO,
+            speed_scales_with_fov: false,
+            start_locked: true,
+            start_cursor_visible: false,
+            editor_mode: false,
+            editor_look_button: MouseButton::Right,
+            // looking at the origin from (1, 1, 1)
+            start_position: Vec3::ONE,
+            start_yaw: std::f32::consts::FRAC_PI_4,
+            start_pitch: -(1.0 / 3.0f32.sqrt()).asin(),
+            keyboard_look_speed: 2.0,
+            gamepad_look_speed: 3.0,
+            look_smoothing: 0.0,
+            look_smoothing_epsilon: 1e-5,
+            look_friction: None,
+            use_real_time: false,
+            max_delta: None,
+            camera_time_scale: 1.0,
+            pause_time_on_unlock: false,
+            use_mouse_motion: true,
+            suppress_look_on_resize: true,
+            recenter_smoothly: false,
+            touch_enabled: false,
+            touch_sensitivity: 1.0,
+            touch_look_split: 0.5,
+            touch_joystick_radius: 100.0,
+            reduced_motion: false,
+            strafe_tilt_degrees: 0.0,
+            head_bob_enabled: false,
+            head_bob_amplitude: 0.05,
+            head_bob_frequency: 1.0,
+            shake_decay: 1.0,
+            shake_max_angle: 0.1,
+            shake_max_offset: 0.05,
+            effect_seed: 0,
+            recenter_duration: 0.25,
+            switch_duration: 0.0,
+            control_all_cameras: true,
+            high_speed_threshold: None,
+            crouch_height: 0.5,
+            crouch_transition_time: 0.15,
+            crouch_easing: ease,
+            dash_distance: 10.0,
+            dash_duration: 0.3,
+            bounds: None,
+            wrap_bounds: None,
+            max_pitch_speed: None,
+            yaw_limits: None,
+            pitch_min: -std::f32::consts::FRAC_PI_2,
+            pitch_max: std::f32::consts::FRAC_PI_2,
+            focus_point: None,
+            speed_distance_scale: |distance| distance,
+            min_movespeed: 0.0,
+            speed_smoothing: 0.0,
+            velocity_smoothing: 0.0,
+            coast: true,
+            stop_on_focus_loss: true,
+            sync_from_transform: false,
+            apply_yaw_pitch: false,
+            fast_movespeed: 4.0,
+            vertical_movespeed: None,
+            sprint_multiplier: 2.0,
+            precision_multiplier: 0.25,
+            movement_enabled: true,
+            movement_mode: MovementMode::Continuous,
+            handedness: Handedness::Right,
+            rail_direction: None,
+            motion_deadzone: 0.0,
+            dpi_independent: false,
+            sensitivity_ramp: |_| 1.0,
+            auto_level_speed: 0.0,
+            six_dof: false,
+            roll_speed: 1.5,
+            gravity_source: None,
+            free_look_return_time: 0.2,
+            look_behind_angle: std::f32::consts::PI,
+            recoil_recovery_time: 0.15,
+        }
+    }
+}
+
+/// Whether `Config::fast_movespeed` is used, as toggled by
+/// `KeyBindings::fast_toggle`. Modify this resource to switch speeds from code.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct FastMovement(pub bool);
+
+/// The world-space direction the movement keys, or the touch joystick, were
+/// moving the controlled camera in this frame, before speed is applied and
+/// without normalizing. Unlike the velocity of the camera, this is the intent
+/// of the player rather than the motion that was applied, so it is still set
+/// when the camera is pushed against its bounds. With several controlled
+/// cameras, this is the input of the last one. It is written by the plugin
+/// every frame, for debug overlays and tests.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct CurrentMovementInput(pub Vec3);
+
+/// A key or mouse button which unlocks the cursor when pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnlockTrigger {
+    /// To avoid accidental unlocks, this can be a combination like
+    /// `Binding::new(KeyCode::Escape, Modifiers { control: true, ..Default::default() })`
+    Key(Binding),
+    Mouse(MouseButton),
+}
+
+/// The keys and mouse buttons which unlock the cursor, any of which unlocks
+/// it. Defaults to Escape. Set this to an empty list to disable the built-in
+/// unlock behaviour entirely, for example if the app wants to use Escape to
+/// open a pause menu and manage the cursor itself. Mouse buttons in this list
+/// no longer lock the cursor when clicked. This is a resource rather than part
+/// of `KeyBindings` so that `Config` stays `Copy`.
+#[derive(Debug, Clone)]
+pub struct UnlockTriggers(pub Vec<UnlockTrigger>);
+
+impl Default for UnlockTriggers {
+    fn default() -> Self {
+        Self(vec![UnlockTrigger::Key(KeyCode::Escape.into())])
+    }
+}
+
+/// The state of the last request to lock the cursor by clicking. Browsers grant
+/// pointer lock asynchronously and may reject it, for example when it is
+/// requested too soon after the pointer was unlocked, which leaves the cursor
+/// unlocked after the click. Read this to show a hint such as "click to capture
+/// the mouse" while a lock is pending or after it failed, rather than leaving
+/// the camera looking stuck. The next click always requests the lock again.
+/// Failed locks are only detected with a `CursorController` which reports
+/// whether the cursor actually is locked, as `WindowCursor` reports the state
+/// which was last requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorLockStatus {
+    /// No lock is waiting to be granted
+    Idle,
+    /// The lock was requested, but the cursor isn't locked yet
+    Pending,
+    /// The lock wasn't granted within a few frames of being requested
+    Failed,
+}
+
+impl Default for CursorLockStatus {
+    fn default() -> Self {
+        Self::Idle
+    }
+}
+
+/// Which rotation axes mouse motion is ignored for, as toggled by
+/// `KeyBindings::lock_pitch` and `KeyBindings::lock_yaw`, for example to pan
+/// without tilting. Modify this resource to lock axes from code.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct LookAxisLocks {
+    pub pitch: bool,
+    pub yaw: bool,
+}
+
+/// A set of movement settings which can be swapped in as a whole, see
+/// `MovementProfiles`. Each field overrides the `Config` field of the same
+/// name while the profile is active.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovementProfile {
+    pub movespeed: f32,
+    pub sprint_multiplier: f32,
+    pub speed_smoothing: f32,
+    pub velocity_smoothing: f32,
+    pub coast: bool,
+    pub movement_mode: MovementMode,
+}
+
+impl MovementProfile {
+    /// The profile matching the current settings of `config`
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            movespeed: config.movespeed,
+            sprint_multiplier: config.sprint_multiplier,
+            speed_smoothing: config.speed_smoothing,
+            velocity_smoothing: config.velocity_smoothing,
+            coast: config.coast,
+            movement_mode: config.movement_mode,
+        }
+    }
+
+    /// Overrides the movement settings of `config` with this profile
+    pub fn apply(&self, config: &mut Config) {
+        config.movespeed = self.movespeed;
+        config.sprint_multiplier = self.sprint_multiplier;
+        config.speed_smoothing = self.speed_smoothing;
+        config.velocity_smoothing = self.velocity_smoothing;
+        config.coast = self.coast;
+        config.movement_mode = self.movement_mode;
+    }
+}
+
+impl Default for MovementProfile {
+    fn default() -> Self {
+        Self::from_config(&Config::default())
+    }
+}
+
+/// Named movement profiles, such as "walk" and "cinematic", of which the
+/// active one overrides the movement settings of `Config`. If no profile is
+/// active, `Config` is used as is. Cameras controlled by a gamepad only use
+/// the `movespeed` of the profile, since the sticks always move them
+/// continuously and without smoothing or sprinting.
+#[derive(Default, Debug, Clone)]
+pub struct MovementProfiles {
+    pub profiles: HashMap<String, MovementProfile>,
+    active: Option<String>,
+}
+
+impl MovementProfiles {
+    /// Adds or replaces the profile called `name`
+    pub fn insert(&mut self, name: impl Into<String>, profile: MovementProfile) {
+        self.profiles.insert(name.into(), profile);
+    }
+
+    /// Makes the profile called `name` active, or deactivates profiles if
+    /// `None`. Returns false, leaving the active profile unchanged, if there
+    /// is no profile with that name.
+    pub fn set_active(&mut self, name: Option<&str>) -> bool {
+        match name {
+            Some(name) if !self.profiles.contains_key(name) => false,
+            name => {
+                self.active = name.map(str::to_owned);
+                true
+            }
+        }
+    }
+
+    /// The name of the active profile
+    pub fn active_name(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    /// The active profile, if any
+    pub fn active(&self) -> Option<&MovementProfile> {
+        self.active
+            .as_ref()
+            .and_then(|name| self.profiles.get(name))
+    }
+}
+
+/// Smoothly changes `Config::sensitivity` to `target` over `duration` seconds.
+/// Insert this resource to start the transition. It is removed once the
+/// transition completes.
+#[derive(Debug, Clone, Copy)]
+pub struct SensitivityTarget {
+    pub target: f32,
+    pub duration: f32,
+    /// The sensitivity when the transition started, and how long it has been
+    /// going on for
+    start: Option<(f32, f32)>,
+}
+
+impl SensitivityTarget {
+    pub fn new(target: f32, duration: f32) -> Self {
+        Self {
+            target,
+            duration,
+            start: None,
         }
     }
 }
 
+/// The projection of the camera spawned by `FpsCamPlugin`. Movement and looking
+/// work the same way for both projections, but with an orthographic projection
+/// moving forwards or backwards does not change the apparent size of objects,
+/// and zooming scales the projection by `Config::zoom_scale` instead of
+/// changing the field of view. The projection of
+/// `OrthographicCameraBundle::new_3d()` is a good starting point for an
+/// orthographic camera.
+#[derive(Clone, Debug)]
+pub enum SpawnProjection {
+    Perspective(PerspectiveProjection),
+    Orthographic(OrthographicProjection),
+}
+
+impl Default for SpawnProjection {
+    fn default() -> Self {
+        Self::Perspective(Default::default())
+    }
+}
+
+/// Configuration for the camera spawned by `FpsCamPlugin`. Insert this resource
+/// before adding the plugin to customize the camera without having to fall
+/// back to `NoSpawnFpsCamPlugin`.
+#[derive(Clone, Debug, Default)]
+pub struct CameraSpawnConfig {
+    pub projection: SpawnProjection,
+    /// If set, this is inserted as the `ClearColor` resource. Note that the
+    /// clear color is shared by all cameras.
+    pub clear_color: Option<Color>,
+    pub render_layers: Option<RenderLayers>,
+    /// If set, the camera starts looking at this point instead of using
+    /// `Config::start_yaw` and `Config::start_pitch`, to frame an object at
+    /// startup. A point straight above or below the start position is
+    /// ignored, since it has no heading to look along
+    pub look_at: Option<Vec3>,
+}
+
 /// Represents the player controlled camera. Attaching this to an entity which
 /// has a transform will make it controllable by the player. Note that if you
 /// put this component on multiple entities they will all be controlled
-/// simultaneously by the player.
+/// simultaneously by the player, unless some of them are marked with
+/// `ActiveFpsCam`.
 #[derive(Component, Default, Debug, Clone, Copy)]
 pub struct FpsCam {
     pub yaw: f32,
     pub pitch: f32,
 }
 
-/// Handles camera movement
-fn camera_move(
-    keys: Res<Input<KeyCode>>,
-    time: Res<Time>,
-    config: Res<Config>,
-    windows: Res<Windows>,
-    mut q: Query<&mut Transform, With<FpsCam>>,
-) {
-    let window = windows.get_primary().unwrap();
-    for mut transform in q.iter_mut() {
-        let mut v = Vec3::ZERO;
-
-        let forward = transform.forward();
-        let right = transform.right();
-
-        if window.cursor_locked() {
-            for key in keys.get_pressed() {
-                match Some(*key) {
-                    x if x == config.key_bindings.forward => v += forward,
-                    x if x == config.key_bindings.back => v -= forward,
-                    x if x == config.key_bindings.left => v -= right,
-                    x if x == config.key_bindings.right => v += right,
-                    x if x == config.key_bindings.up => v += Vec3::Y,
-                    x if x == config.key_bindings.down => v -= Vec3::Y,
-
-                    _ => (),
-                }
+impl FpsCam {
+    /// The position, yaw and pitch of the camera with this component and
+    /// `transform`
+    pub fn pose(&self, transform: &Transform) -> (Vec3, f32, f32) {
+        (transform.translation, self.yaw, self.pitch)
+    }
+
+    /// Sets the position, yaw and pitch of the camera with this component and
+    /// `transform` together. Use this instead of setting the transform
+    /// directly when programmatically repositioning the camera, so that the
+    /// view doesn't snap back to the old yaw and pitch on the next mouse
+    /// movement. The pitch is limited and the rotation composed the same way
+    /// the plugin does with `config`, including `Config::gravity_source`.
+    pub fn set_pose(
+        &mut self,
+        transform: &mut Transform,
+        position: Vec3,
+        yaw: f32,
+        pitch: f32,
+        config: &Config,
+    ) {
+        self.yaw = yaw;
+        self.pitch = limit_pitch(pitch, config);
+
+        transform.translation = position;
+        transform.rotation = up_basis(config, position) * look_rotation(self.yaw, self.pitch);
+    }
+}
+
+/// A snapshot of the state of a camera, for example to quickly save and load
+/// viewpoints while debugging
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FpsCamState {
+    pub transform: Transform,
+    pub yaw: f32,
+    pub pitch: f32,
+    /// The smoothed velocity the camera was moving with when the snapshot was
+    /// taken, see `Config::velocity_smoothing`
+    pub smoothed_velocity: Vec3,
+    /// The smoothed movement speed when the snapshot was taken, see
+    /// `Config::speed_smoothing`
+    pub speed: Option<f32>,
+}
+
+impl FpsCamState {
+    /// Captures the state of the camera `entity`, if it is an `FpsCam`. The
+    /// transform is captured without the view offset, like eye height or
+    /// shake, which is applied on top of it.
+    pub fn capture(world: &World, entity: Entity) -> Option<Self> {
+        let mut transform = *world.get::<Transform>(entity)?;
+        let fpscam = world.get::<FpsCam>(entity)?;
+        // matches how `reset_camera_state` removes the offset
+        if let Some(offset) = world.get::<ViewOffset>(entity) {
+            if transform.translation == offset.rendered.translation {
+                transform.translation -= offset.translation;
+            }
+            if transform.rotation == offset.rendered.rotation {
+                transform.rotation *= offset.rotation.inverse();
             }
         }
+        let movement = world
+            .get::<MovementState>(entity)
+            .copied()
+            .unwrap_or_default();
 
-        v = v.normalize_or_zero();
+        Some(Self {
+            transform,
+            yaw: fpscam.yaw,
+            pitch: fpscam.pitch,
+            smoothed_velocity: movement.smoothed_velocity,
+            speed: movement.speed,
+        })
+    }
 
-        transform.translation += v * time.delta_seconds() * config.movespeed;
+    /// Restores the camera `entity` to this state. The camera keeps the
+    /// momentum it had when the snapshot was taken, and any other motion which
+    /// hadn't been applied yet is discarded.
+    pub fn restore(&self, world: &mut World, entity: Entity) {
+        if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+            *transform = self.transform;
+        }
+        if let Some(mut fpscam) = world.get_mut::<FpsCam>(entity) {
+            fpscam.yaw = self.yaw;
+            fpscam.pitch = self.pitch;
+        }
+        // the restored transform has no offset to remove
+        if let Some(mut offset) = world.get_mut::<ViewOffset>(entity) {
+            offset.translation = Vec3::ZERO;
+            offset.rotation = Quat::IDENTITY;
+        }
+        if let Some(mut movement) = world.get_mut::<MovementState>(entity) {
+            movement.smoothed_velocity = self.smoothed_velocity;
+            movement.speed = self.speed;
+        }
+        if let Some(mut look) = world.get_mut::<LookState>(entity) {
+            look.pending = Vec2::ZERO;
+            look.angular_velocity = Vec2::ZERO;
+            look.recenter = None;
+        }
     }
 }
 
-/// Handles camera looking, only when the cursor is locked
-fn camera_look(
-    config: Res<Config>,
-    windows: Res<Windows>,
-    mut motion: EventReader<MouseMotion>,
-    mut q: Query<(&mut Transform, &mut FpsCam)>,
-) {
-    let window = windows.get_primary().unwrap();
-    for (mut transform, mut fpscam) in q.iter_mut() {
-        for event in motion.iter() {
-            if window.cursor_locked() {
-                fpscam.yaw -= config.sensitivity * event.delta.x;
-                fpscam.pitch -= config.sensitivity * event.delta.y;
+/// Marks the camera that should currently be controlled by the player. If any
+/// `FpsCam` has this marker, only the marked cameras respond to input. If no
+/// camera is marked, all of them are controlled, unless
+/// `Config::control_all_cameras` is disabled. Moving the marker from one
+/// entity to another switches control between cameras, blending between them
+/// if `Config::switch_duration` is set.
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct ActiveFpsCam;
 
-                fpscam.pitch = fpscam
-                    .pitch
-                    .clamp(-std::f32::consts::PI / 2.0, std::f32::consts::PI / 2.0);
+/// Makes the camera automatically orbit around `center` at the given `radius`
+/// and `height` above it, facing inwards. `speed` is in radians per second.
+/// While this component is present, manual movement and looking are
+/// suppressed. Once it is removed, manual control resumes from the current
+/// orientation. The view is kept within the look limits of `Config`, so it
+/// only faces the center while that is within them.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Orbit {
+    pub center: Vec3,
+    pub radius: f32,
+    pub speed: f32,
+    pub height: f32,
+}
 
-                transform.rotation = Quat::from_axis_angle(Vec3::Y, fpscam.yaw)
-                    * Quat::from_axis_angle(Vec3::X, fpscam.pitch);
-            }
-        }
+/// Offsets the rendered camera from its logical position, for example to place
+/// it at eye height above the base of a character. The offset is in local space
+/// but is only rotated by the yaw of the camera, so a vertical offset stays
+/// vertical when looking up or down.
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct EyeOffset(pub Vec3);
+
+/// Multiplies the mouse sensitivity of just this camera, for example for a
+/// scope camera which should turn slower than the main camera
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SensitivityScale(pub f32);
+
+impl Default for SensitivityScale {
+    fn default() -> Self {
+        Self(1.0)
     }
 }
 
-/// Handles matching the cursor lock state when the window gains or loses focus
-fn lock_on_focus(mut windows: ResMut<Windows>, mut focus_events: EventReader<WindowFocused>) {
-    let window = windows.get_primary_mut().unwrap();
-    for ev in focus_events.iter() {
-        if ev.id == window.id() {
-            set_cursor_lock(window, ev.focused);
-        }
+/// Shakes the camera, for example as feedback for an impact. The shake is
+/// proportional to the square of `trauma`, which ranges from 0 to 1 and decays
+/// over time at the rate given by `Config::shake_decay`. The shake is applied on
+/// top of the look rotation, so it doesn't affect the yaw and pitch of the
+/// camera.
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct CameraShake {
+    pub trauma: f32,
+}
+
+impl CameraShake {
+    /// Adds trauma to the camera, for example every time it is hit
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
     }
 }
 
-/// Handles unlocking the cursor when the key is pressed
-fn unlock_cursor(
-    config: Res<Config>,
-    mut windows: ResMut<Windows>,
-    mut key_events: EventReader<KeyboardInput>,
-) {
-    let window = windows.get_primary_mut().unwrap();
-    for kev in key_events.iter() {
-        if let Some(code) = kev.key_code {
-            if Some(code) == config.key_bindings.unlock {
-                set_cursor_lock(window, false);
-            }
-        }
+/// The offset currently applied on top of the logical position and rotation
+/// of a camera. It is removed again before the camera is controlled, so that
+/// it never accumulates into the logical transform.
+#[derive(Component, Default, Debug, Clone, Copy)]
+struct ViewOffset {
+    translation: Vec3,
+    rotation: Quat,
+    /// The transform after the offset was applied, to detect whether the
+    /// transform has been set externally since
+    rendered: Transform,
+    /// The current strafe tilt, in radians
+    tilt: f32,
+    /// The phase of the head-bob, in radians
+    bob_phase: f32,
+    /// How strongly the head-bob is currently applied, from 0 to 1
+    bob_intensity: f32,
+    /// The position in the noise used for camera shake
+    shake_time: f32,
+    /// How far the crouch transition has progressed, from 0 to 1
+    crouch_progress: f32,
+}
+
+/// How the player moved a camera this frame
+#[derive(Component, Default, Debug, Clone, Copy)]
+struct MovementState {
+    velocity: Vec3,
+    /// The smoothed movement speed, see `Config::speed_smoothing`
+    speed: Option<f32>,
+    /// The smoothed velocity of movement from the movement keys, see
+    /// `Config::velocity_smoothing`
+    smoothed_velocity: Vec3,
+    /// The sides of the bounds the camera was clamped against last frame
+    bounds_contact: Vec3,
+    /// Whether the camera was above `Config::high_speed_threshold` last frame
+    high_speed: bool,
+}
+
+/// The timestep used by all time based camera behaviour this frame
+#[derive(Default, Debug, Clone, Copy)]
+struct CameraTime {
+    delta: f32,
+    /// The timestep for smoothing and transitions, scaled by
+    /// `Config::camera_time_scale`
+    smoothing_delta: f32,
+    last_update: Option<Instant>,
+    /// The fixed timestep the controls run at, if set up with
+    /// `FixedTimestepFpsCamPlugin`
+    fixed_step: Option<f32>,
+}
+
+/// A source of look motion. Implement this for an event type and add a
+/// `MotionSourcePlugin` for it to feed the events into the camera look, for
+/// example to read raw device motion from another plugin instead of the
+/// possibly OS-accelerated `MouseMotion`.
+pub trait MotionSource: Send + Sync + 'static {
+    /// The motion, in the same units as the delta of `MouseMotion`
+    fn motion_delta(&self) -> Vec2;
+}
+
+/// Look motion from an arbitrary source, such as a replay system or remote
+/// control, in the same units as the delta of `MouseMotion`. Send these events
+/// to turn the camera. They are used along with `MouseMotion`, unless
+/// `Config::use_mouse_motion` is disabled.
+#[derive(Debug, Clone, Copy)]
+pub struct LookInput(pub Vec2);
+
+impl MotionSource for LookInput {
+    fn motion_delta(&self) -> Vec2 {
+        self.0
     }
 }
 
-/// Handles locking the cursor when the client area is clicked
-fn lock_cursor(mut windows: ResMut<Windows>, mut mouse_events: EventReader<MouseButtonInput>) {
-    let window = windows.get_primary_mut().unwrap();
-    for ev in mouse_events.iter() {
-        if ev.state == ElementState::Pressed {
-            set_cursor_lock(window, true);
-        }
+/// The last camera marked with `ActiveFpsCam` and its transform, to blend from
+/// when another camera becomes active
+#[derive(Default, Debug, Clone, Copy)]
+struct PreviousActiveCamera(Option<(Entity, Transform)>);
+
+/// The look motion received from all sources this frame
+#[derive(Default, Debug, Clone, Copy)]
+struct LookMotion(Vec2);
+
+/// The deflection of the virtual touch joystick this frame, with the length
+/// limited to 1
+#[derive(Default, Debug, Clone, Copy)]
+struct TouchMovement(Vec2);
+
+/// The keys pressed while the cursor was locked since the cameras last
+/// advanced. Bindings which trigger once per press, like `fast_toggle`, are
+/// read from this rather than from `Input<KeyCode>`, so that with a fixed
+/// timestep every press is handled by exactly one step, no matter how many
+/// steps a frame runs.
+#[derive(Default, Debug, Clone)]
+struct PendingPresses(Vec<KeyCode>);
+
+impl PendingPresses {
+    /// Whether `binding` was pressed since the cameras last advanced
+    fn just_pressed(&self, binding: Option<Binding>, keys: &Input<KeyCode>) -> bool {
+        binding
+            .filter(|binding| binding.enabled)
+            .map_or(false, |binding| {
+                self.0.contains(&binding.key) && binding.modifiers.held(|key| keys.pressed(key))
+            })
     }
 }
 
-/// Spawns the camera
-fn spawn_camera(mut cmd: Commands) {
-    cmd.spawn_bundle(PerspectiveCameraBundle {
-        transform: Transform::from_xyz(1.0, 1.0, 1.0).looking_at(Vec3::ZERO, Vec3::Y),
-        ..Default::default()
-    })
-    .insert(FpsCam::default());
+/// The order in which cameras appeared, for picking the first spawned camera
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct SpawnOrder(u64);
+
+/// Look state of a camera which persists between frames
+#[derive(Component, Default, Debug, Clone, Copy)]
+struct LookState {
+    /// Mouse motion which has been received but not yet applied, because of
+    /// look smoothing
+    pending: Vec2,
+    /// The pitch at which an ongoing pitch recenter started, and how long it
+    /// has been going on for
+    recenter: Option<(f32, f32)>,
+    /// How far the view is turned away from the direction of movement by free
+    /// look, in radians
+    free_look_yaw: f32,
+    /// Whether the view is turned around by the look behind key
+    look_behind: bool,
+    /// How fast the camera keeps turning without mouse motion, in radians per
+    /// second, for `Config::look_friction`
+    angular_velocity: Vec2,
+    /// How long the mouse has been moving without stopping, for
+    /// `Config::sensitivity_ramp`
+    motion_time: f32,
+    /// The current recoil offset of the view, as yaw and pitch in radians
+    recoil: Vec2,
+    /// The free look yaw at which returning to the direction of movement
+    /// started, and how long it has been going on for
+    free_look_return: Option<(f32, f32)>,
 }
 
-fn set_cursor_lock(window: &mut Window, state: bool) {
-    window.set_cursor_lock_mode(state);
-    window.set_cursor_visibility(!state);
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+enum FpsCamSystem {
+    ReadMotion,
+    LatchPresses,
+    Time,
+    Reset,
+    Control,
+    ApplyOffset,
 }
 
-/// Spawns a camera and sets up the controls.
-pub struct FpsCamPlugin;
-impl Plugin for FpsCamPlugin {
-    fn build(&self, app: &mut App) {
-        app.init_resource::<Config>()
-            .add_startup_system(spawn_camera)
-            .add_system(camera_move)
-            .add_system(camera_look)
-            .add_system(lock_on_focus)
-            .add_system(lock_cursor)
-            .add_system(unlock_cursor);
+/// The time constant, in seconds, with which the strafe tilt and head-bob ease
+/// in and out
+const EFFECT_EASE_TIME: f32 = 0.1;
+
+/// For converting the dpi of mice, see `Config::from_cm_per_360`
+const CM_PER_INCH: f32 = 2.54;
+
+/// The fastest, in radians per second, that the look bindings, the gamepad and
+/// pitching with the mouse turn the camera with `Config::reduced_motion`
+const REDUCED_MOTION_TURN_SPEED: f32 = 1.0;
+
+/// How many random jolts per second camera shake consists of
+const SHAKE_FREQUENCY: f32 = 15.0;
+
+/// For how many frames after the window was resized or moved mouse motion is
+/// ignored, see `Config::suppress_look_on_resize`
+const RESIZE_SUPPRESS_FRAMES: u32 = 2;
+
+/// For how many frames after the cursor was locked again mouse motion is
+/// ignored, so that warping the cursor back doesn't turn the camera, see
+/// `Config::recenter_smoothly`
+const RELOCK_SUPPRESS_FRAMES: u32 = 2;
+
+/// How many frames a lock requested by clicking may take to be granted before
+/// it is considered failed, see `CursorLockStatus`
+const LOCK_REQUEST_FRAMES: u32 = 10;
+
+/// Over how many frames the cursor glides to the center of the window, see
+/// `Config::recenter_smoothly`
+const RECENTER_CURSOR_FRAMES: u32 = 6;
+
+/// The speed below which a coasting camera is stopped entirely
+const COAST_EPSILON: f32 = 1e-4;
+
+/// The window height, in physical pixels, at which mouse motion is unscaled
+/// when `Config::dpi_independent` is enabled
+const REFERENCE_WINDOW_HEIGHT: f32 = 1080.0;
+
+/// Filter for cameras which are not currently being driven automatically
+type ManualControl = (Without<MoveTo>, Without<Orbit>, Without<PathPlayback>);
+
+/// Sent whenever a camera is moved by the player
+#[derive(Debug, Clone, Copy)]
+pub struct FpsCamMoved {
+    pub entity: Entity,
+    pub delta: Vec3,
+}
+
+/// Sent whenever a camera is rotated by the player, containing the new yaw and
+/// pitch
+#[derive(Debug, Clone, Copy)]
+pub struct FpsCamLooked {
+    pub entity: Entity,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+/// Sent when a camera is moved into one of the sides of `Config::bounds`.
+/// `axis` points towards the side that was hit, for example `-Vec3::X` for the
+/// minimum x bound. This is only sent once when the camera first hits the
+/// side, not continuously while it is pushed against it.
+#[derive(Debug, Clone, Copy)]
+pub struct BoundsHit {
+    pub entity: Entity,
+    pub axis: Vec3,
+}
+
+/// Sent when the movement speed of a camera rises above
+/// `Config::high_speed_threshold`, with `entered` set, and again when it drops
+/// back below it, for effects like speed lines or a kick of the field of view
+#[derive(Debug, Clone, Copy)]
+pub struct HighSpeed {
+    pub entity: Entity,
+    pub speed: f32,
+    pub entered: bool,
+}
+
+/// Send this event to kick the view of the controlled cameras by `yaw` and
+/// `pitch` radians, for example when firing a weapon. The view then recovers
+/// over `Config::recoil_recovery_time`. The recoil is kept apart from the yaw
+/// and pitch of the `FpsCam`, so aiming while recovering isn't undone.
+#[derive(Debug, Clone, Copy)]
+pub struct RecoilKick {
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+/// Present on cameras while they are zoomed in or transitioning back out
+#[derive(Component, Debug, Clone, Copy)]
+struct Zoomed {
+    /// The field of view, or the scale for orthographic cameras, to return to
+    /// once the zoom key is released
+    base: f32,
+    /// How far the zoom transition has progressed, from 0 to 1
+    progress: f32,
+    /// How much the view is currently magnified, as the size of the current
+    /// view relative to the unzoomed view
+    ratio: f32,
+    /// Whether this is aiming down sights rather than a plain zoom, decided
+    /// when the zoom starts
+    ads: bool,
+}
+
+/// Present on cameras while the player is moving them, added and removed by the
+/// plugin every frame, so that other systems can react to movement with
+/// `With<Moving>` or `Added<Moving>`, for example to play footsteps
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct Moving;
+
+/// Excludes a camera from managing the cursor. The cursor is only locked,
+/// unlocked and matched to the window focus as long as at least one
+/// controlled camera does not have this marker. When some cameras are marked
+/// with `ActiveFpsCam`, only those are considered, so if every active camera
+/// has this marker the cursor is left alone even if other cameras don't.
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct NoCursorControl;
+
+/// The input device controlling a camera, for local multiplayer where every
+/// player controls their own camera. Cameras without this component are
+/// controlled by the keyboard and mouse.
+///
+/// A camera with `PlayerInput::Gamepad` is moved with the left stick, raised
+/// and lowered with the right and left triggers, and turned with the right
+/// stick. It is controlled regardless of `ActiveFpsCam` and whether the cursor
+/// is locked, and ignores the keyboard and mouse entirely.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerInput {
+    KeyboardMouse,
+    Gamepad(Gamepad),
+}
+
+/// The input device which was used last, for example to show matching control
+/// hints. This only changes when a different device is used, so change
+/// detection can be used to react to switching devices. Switches back to the
+/// keyboard and mouse when the last used gamepad is disconnected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LastInputDevice(pub PlayerInput);
+
+impl Default for LastInputDevice {
+    fn default() -> Self {
+        Self(PlayerInput::KeyboardMouse)
     }
 }
 
-/// Sets up the controls, but does not actually spawn a camera.
-pub struct NoSpawnFpsCamPlugin;
-impl Plugin for NoSpawnFpsCamPlugin {
-    fn build(&self, app: &mut App) {
+/// The height of the terrain, for keeping cameras above it without physics.
+/// Insert this resource to enable it. After being controlled, the height of a
+/// camera is clamped to at least `offset` above the height returned by
+/// `height` for its horizontal position, or set to exactly that if `snap` is
+/// set, so that the camera walks along the terrain.
+pub struct GroundHeight {
+    /// Maps a horizontal position, as X and Z, to the height of the terrain
+    pub height: Box<dyn Fn(Vec2) -> f32 + Send + Sync>,
+    pub offset: f32,
+    pub snap: bool,
+}
+
+impl GroundHeight {
+    pub fn new(height: impl Fn(Vec2) -> f32 + Send + Sync + 'static, offset: f32) -> Self {
+        Self {
+            height: Box::new(height),
+            offset,
+            snap: false,
+        }
+    }
+}
+
+/// The cameras which may manage the cursor, along with whether they are active
+type CursorCameras<'w, 's> =
+    Query<'w, 's, Option<&'static ActiveFpsCam>, (With<FpsCam>, Without<NoCursorControl>)>;
+
+/// Smoothly moves the camera to `target` over `duration` seconds. While this
+/// component is present, manual movement and looking are suppressed. Once the
+/// transition completes the component is removed and the yaw and pitch of the
+/// `FpsCam` are updated to match the target rotation. A target rotation beyond
+/// the look limits of `Config` ends up at the limits instead.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MoveTo {
+    pub target: Transform,
+    pub duration: f32,
+    pub elapsed: f32,
+}
+
+impl MoveTo {
+    pub fn new(target: Transform, duration: f32) -> Self {
+        Self {
+            target,
+            duration,
+            elapsed: 0.0,
+        }
+    }
+}
+
+/// Marks a `MoveTo` as having been started by a dash, so that it can be
+/// cancelled by movement input
+#[derive(Component, Default, Debug, Clone, Copy)]
+struct Dashing;
+
+/// A recorded camera path, as transforms along with the time in seconds since
+/// the start of the recording at which they were captured
+#[derive(Default, Debug, Clone)]
+pub struct CameraPath {
+    pub keyframes: Vec<(f32, Transform)>,
+}
+
+impl CameraPath {
+    /// How long the path takes to play back, in seconds
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |(time, _)| *time)
+    }
+
+    /// The interpolated transform along the path at `time` seconds
+    pub fn sample(&self, time: f32) -> Option<Transform> {
+        let next = self.keyframes.iter().position(|(t, _)| *t > time);
+        match next {
+            Some(0) => self.keyframes.first().map(|(_, transform)| *transform),
+            Some(i) => {
+                let (t0, a) = self.keyframes[i - 1];
+                let (t1, b) = self.keyframes[i];
+                let s = (time - t0) / (t1 - t0);
+                Some(Transform {
+                    translation: a.translation.lerp(b.translation, s),
+                    rotation: a.rotation.slerp(b.rotation, s),
+                    scale: a.scale.lerp(b.scale, s),
+                })
+            }
+            None => self.keyframes.last().map(|(_, transform)| *transform),
+        }
+    }
+}
+
+/// Records the path of the camera while present. Insert this component to
+/// start recording, and read `path` before removing it to stop.
+#[derive(Component, Debug, Clone)]
+pub struct PathRecorder {
+    /// How often a keyframe is captured, in seconds
+    pub interval: f32,
+    pub path: CameraPath,
+    elapsed: f32,
+    next_keyframe: f32,
+}
+
+impl PathRecorder {
+    pub fn new(interval: f32) -> Self {
+        Self {
+            interval,
+            path: CameraPath::default(),
+            elapsed: 0.0,
+            next_keyframe: 0.0,
+        }
+    }
+}
+
+/// Plays back a recorded path on the camera, interpolating between the
+/// keyframes. While this component is present, manual movement and looking
+/// are suppressed. Once playback completes the component is removed and the
+/// yaw and pitch of the `FpsCam` match the end of the path. Rotations beyond
+/// the look limits of `Config` are played back at the limits.
+#[derive(Component, Debug, Clone)]
+pub struct PathPlayback {
+    pub path: CameraPath,
+    pub elapsed: f32,
+}
+
+impl PathPlayback {
+    pub fn new(path: CameraPath) -> Self {
+        Self { path, elapsed: 0.0 }
+    }
+}
+
+/// Handles camera movement
+fn camera_move(
+    mut cmd: Commands,
+    keys: Res<Input<KeyCode>>,
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    windows: Res<Windows>,
+    cursor: Res<CursorControl>,
+    presses: Res<PendingPresses>,
+    mut fast: ResMut<FastMovement>,
+    touch: Res<TouchMovement>,
+    profiles: Res<MovementProfiles>,
+    mut current_input: ResMut<CurrentMovementInput>,
+    mut moved: EventWriter<FpsCamMoved>,
+    mut bounds_hit: EventWriter<BoundsHit>,
+    mut high_speed: EventWriter<HighSpeed>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    mut q: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut FpsCam,
+            &mut MovementState,
+            Option<&ActiveFpsCam>,
+            Option<&PlayerInput>,
+            Option<&Zoomed>,
+            Option<&Moving>,
+        ),
+        ManualControl,
+    >,
+) {
+    current_input.0 = Vec3::ZERO;
+    if !config.movement_enabled {
+        for (entity, .., moving) in q.iter() {
+            set_moving(&mut cmd, entity, moving, false);
+        }
+        return;
+    }
+
+    let locked = cursor.is_locked(&windows);
+    if locked && presses.just_pressed(config.key_bindings.fast_toggle, &keys) {
+        fast.0 = !fast.0;
+    }
+
+    let any_active = !active.is_empty();
+
+    // the held keys are read from the input state rather than from events, so
+    // keys which were already held while the cursor was unlocked resume
+    // moving the camera as soon as it is locked again
+    let (pressed, just_pressed): (Vec<KeyCode>, Vec<KeyCode>) = if locked {
+        (keys.get_pressed().copied().collect(), presses.0.clone())
+    } else {
+        Default::default()
+    };
+
+    for (entity, mut transform, mut fpscam, mut movement, is_active, input, zoomed, moving) in
+        q.iter_mut()
+    {
+        // cameras controlled by a gamepad are handled by `camera_gamepad`
+        if !uses_shared_input(input) {
+            continue;
+        }
+        if !is_controlled(any_active, is_active) {
+            set_moving(&mut cmd, entity, moving, false);
+            continue;
+        }
+
+        let mut config = movement_config(&config, &profiles, fast.0);
+        if let Some(zoomed) = zoomed.filter(|_| config.speed_scales_with_fov) {
+            config.movespeed *= zoomed.ratio;
+        }
+
+        let touch_input = (touch.0 != Vec2::ZERO).then(|| {
+            let (forward, right, _) = movement_axes(&transform, &config);
+            forward * touch.0.y + right * touch.0.x
+        });
+        current_input.0 = touch_input.unwrap_or_else(|| {
+            movement_input(&transform, &pressed, &config, |key| pressed.contains(&key))
+        });
+
+        let before = transform.translation;
+        let contact = match config.movement_mode {
+            // the touch joystick is analog, so it isn't combined with the
+            // movement keys
+            _ if touch_input.is_some() => {
+                let v = current_input.0;
+                let speed = config.movespeed.max(config.min_movespeed);
+                let translation = transform.translation + v * speed * time.delta;
+                move_within_bounds(&mut transform, translation, &config)
+            }
+            MovementMode::Continuous => {
+                let target = movement_speed(&transform, &pressed, &config);
+                let speed = match movement.speed {
+                    Some(speed) if config.speed_smoothing > 0.0 => {
+                        ease_towards(speed, target, time.smoothing_delta, config.speed_smoothing)
+                    }
+                    _ => target,
+                };
+                movement.speed = Some(speed);
+
+                if config.velocity_smoothing > 0.0 {
+                    let mut velocity = movement_velocity(
+                        &transform,
+                        &pressed,
+                        &config,
+                        |key| pressed.contains(&key),
+                        speed,
+                    );
+                    let has_input = velocity != Vec3::ZERO;
+
+                    if velocity != Vec3::ZERO || config.coast {
+                        let s = 1.0 - (-time.smoothing_delta / config.velocity_smoothing).exp();
+                        velocity = movement.smoothed_velocity.lerp(velocity, s);
+                        if !has_input && velocity.length() < COAST_EPSILON {
+                            velocity = Vec3::ZERO;
+                        }
+                    }
+                    movement.smoothed_velocity = velocity;
+
+                    let translation = transform.translation + velocity * time.delta;
+                    move_within_bounds(&mut transform, translation, &config)
+                } else {
+                    move_at_speed(&mut transform, &pressed, &config, speed, time.delta)
+                }
+            }
+            MovementMode::Step { step } => apply_step_movement(
+                &mut transform,
+                &just_pressed,
+                |key| pressed.contains(&key),
+                &config,
+                step,
+            ),
+        };
+
+        report_bounds_contact(entity, contact, &mut movement, &mut bounds_hit);
+
+        let delta = transform.translation - before;
+        wrap_around(&mut transform, &config);
+        set_moving(&mut cmd, entity, moving, delta != Vec3::ZERO);
+        if delta != Vec3::ZERO {
+            if config.gravity_source.is_some() && !config.six_dof {
+                transport_view(&mut transform, &mut fpscam, &config, before);
+            }
+
+            if time.delta > 0.0 {
+                movement.velocity = delta / time.delta;
+            }
+            moved.send(FpsCamMoved { entity, delta });
+        }
+
+        report_speed(entity, &config, &mut movement, &mut high_speed);
+    }
+}
+
+/// `config` with the active movement profile, reduced motion and
+/// `FastMovement` applied, as the cameras are moved with
+fn movement_config(config: &Config, profiles: &MovementProfiles, fast: bool) -> Config {
+    let mut config = *config;
+    if let Some(profile) = profiles.active() {
+        profile.apply(&mut config);
+    }
+    // after the profile, so that it can't bring back smoothing
+    let mut config = config.with_reduced_motion();
+    if fast {
+        config.movespeed = config.fast_movespeed;
+    }
+    config
+}
+
+/// Sends a `BoundsHit` for every side of the bounds in `contact` which the
+/// camera wasn't already against
+fn report_bounds_contact(
+    entity: Entity,
+    contact: Vec3,
+    movement: &mut MovementState,
+    bounds_hit: &mut EventWriter<BoundsHit>,
+) {
+    for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+        let side = contact.dot(axis);
+        if side != 0.0 && side != movement.bounds_contact.dot(axis) {
+            bounds_hit.send(BoundsHit {
+                entity,
+                axis: axis * side,
+            });
+        }
+    }
+    movement.bounds_contact = contact;
+}
+
+/// Sends `HighSpeed` when the speed of a camera crosses
+/// `Config::high_speed_threshold`
+fn report_speed(
+    entity: Entity,
+    config: &Config,
+    movement: &mut MovementState,
+    high_speed: &mut EventWriter<HighSpeed>,
+) {
+    let speed = movement.velocity.length();
+    let above = config
+        .high_speed_threshold
+        .map_or(false, |threshold| speed > threshold);
+    if above != movement.high_speed {
+        high_speed.send(HighSpeed {
+            entity,
+            speed,
+            entered: above,
+        });
+    }
+    movement.high_speed = above;
+}
+
+/// Handles moving and turning cameras controlled by a gamepad
+fn camera_gamepad(
+    mut cmd: Commands,
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    axes: Res<Axis<GamepadAxis>>,
+    buttons: Res<Input<GamepadButton>>,
+    fast: Res<FastMovement>,
+    profiles: Res<MovementProfiles>,
+    mut moved: EventWriter<FpsCamMoved>,
+    mut looked: EventWriter<FpsCamLooked>,
+    mut bounds_hit: EventWriter<BoundsHit>,
+    mut high_speed: EventWriter<HighSpeed>,
+    mut q: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut FpsCam,
+            &mut MovementState,
+            &PlayerInput,
+            Option<&Moving>,
+        ),
+        ManualControl,
+    >,
+) {
+    let config = movement_config(&config, &profiles, fast.0);
+    for (entity, mut transform, mut fpscam, mut movement, input, moving) in q.iter_mut() {
+        let gamepad = match *input {
+            PlayerInput::Gamepad(gamepad) => gamepad,
+            PlayerInput::KeyboardMouse => continue,
+        };
+        let axis = |axis_type| axes.get(GamepadAxis(gamepad, axis_type)).unwrap_or(0.0);
+        let pressed = |button_type| buttons.pressed(GamepadButton(gamepad, button_type));
+
+        let look = Vec2::new(
+            axis(GamepadAxisType::RightStickX),
+            axis(GamepadAxisType::RightStickY),
+        ) * config.gamepad_look_speed
+            * time.delta;
+        if look != Vec2::ZERO {
+            turn(&mut transform, &mut fpscam, &config, -look.x, look.y);
+
+            looked.send(FpsCamLooked {
+                entity,
+                yaw: fpscam.yaw,
+                pitch: fpscam.pitch,
+            });
+        }
+
+        let mut is_moving = false;
+        if config.movement_enabled {
+            let (forward, right, up) = movement_axes(&transform, &config);
+            let mut v = forward * axis(GamepadAxisType::LeftStickY)
+                + right * axis(GamepadAxisType::LeftStickX);
+            if pressed(GamepadButtonType::RightTrigger2) {
+                v += up;
+            }
+            if pressed(GamepadButtonType::LeftTrigger2) {
+                v -= up;
+            }
+
+            // the sticks are analog, so only overly long input is shortened
+            // rather than normalizing it
+            let before = transform.translation;
+            let translation = before
+                + v.clamp_length_max(1.0) * config.movespeed.max(config.min_movespeed) * time.delta;
+            let contact = move_within_bounds(&mut transform, translation, &config);
+            report_bounds_contact(entity, contact, &mut movement, &mut bounds_hit);
+
+            let delta = transform.translation - before;
+            wrap_around(&mut transform, &config);
+            is_moving = delta != Vec3::ZERO;
+            if delta != Vec3::ZERO {
+                if config.gravity_source.is_some() && !config.six_dof {
+                    transport_view(&mut transform, &mut fpscam, &config, before);
+                }
+
+                if time.delta > 0.0 {
+                    movement.velocity = delta / time.delta;
+                }
+                moved.send(FpsCamMoved { entity, delta });
+            }
+
+            report_speed(entity, &config, &mut movement, &mut high_speed);
+        }
+
+        set_moving(&mut cmd, entity, moving, is_moving);
+    }
+}
+
+/// Adds or removes the `Moving` marker of a camera if it changed
+fn set_moving(cmd: &mut Commands, entity: Entity, moving: Option<&Moving>, is_moving: bool) {
+    if is_moving && moving.is_none() {
+        cmd.entity(entity).insert(Moving);
+    } else if !is_moving && moving.is_some() {
+        cmd.entity(entity).remove::<Moving>();
+    }
+}
+
+/// Moves `transform` according to the held keys in `keys_pressed`, as
+/// configured by `config`, over a timestep of `dt` seconds. This is the
+/// movement logic used by the plugin, exposed so that movement can be
+/// scripted or reproduced without running a bevy app. Returns the sides of
+/// `config.bounds` the movement was clamped against, as -1 or 1 for the
+/// minimum or maximum side of each axis, or 0 if it wasn't clamped.
+pub fn apply_movement(
+    transform: &mut Transform,
+    keys_pressed: &[KeyCode],
+    config: &Config,
+    dt: f32,
+) -> Vec3 {
+    let speed = movement_speed(transform, keys_pressed, config);
+    move_at_speed(transform, keys_pressed, config, speed, dt)
+}
+
+/// The normalized world-space direction the held movement keys would move a
+/// camera with `transform` in, without moving it, or zero if no movement keys
+/// are held. This allows reusing the key mapping of the plugin, for example to
+/// drive a physics based character controller.
+pub fn movement_direction(keys: &Input<KeyCode>, config: &Config, transform: &Transform) -> Vec3 {
+    let pressed: Vec<KeyCode> = keys.get_pressed().copied().collect();
+    movement_input(transform, &pressed, config, |key| keys.pressed(key)).normalize_or_zero()
+}
+
+/// The speed `apply_movement` moves a camera at with the held keys in
+/// `keys_pressed`, taking sprinting, precision movement and
+/// `Config::focus_point` into account
+pub fn movement_speed(transform: &Transform, keys_pressed: &[KeyCode], config: &Config) -> f32 {
+    let pressed = |key| keys_pressed.contains(&key);
+    let mut speed = config.movespeed * speed_multiplier(config, pressed);
+
+    if let Some(focus_point) = config.focus_point {
+        speed *= (config.speed_distance_scale)(transform.translation.distance(focus_point));
+    }
+    speed.max(config.min_movespeed)
+}
+
+/// What the movement speed is multiplied by for sprinting or precision
+/// movement, given the keys for which `pressed` returns true
+fn speed_multiplier(config: &Config, pressed: impl Fn(KeyCode) -> bool + Copy) -> f32 {
+    let bindings = &config.key_bindings;
+    // precision takes precedence over sprinting when both are held
+    if held_in(bindings.precision, pressed) {
+        config.precision_multiplier
+    } else if held_in(bindings.sprint, pressed) {
+        config.sprint_multiplier
+    } else {
+        1.0
+    }
+}
+
+/// Moves `transform` like `apply_movement`, but at the given speed
+fn move_at_speed(
+    transform: &mut Transform,
+    keys_pressed: &[KeyCode],
+    config: &Config,
+    speed: f32,
+    dt: f32,
+) -> Vec3 {
+    let pressed = |key| keys_pressed.contains(&key);
+    let v = movement_velocity(transform, keys_pressed, config, pressed, speed);
+
+    let translation = transform.translation + v * dt;
+    move_within_bounds(transform, translation, config)
+}
+
+/// The velocity the held keys move a camera at, at `speed`, or at
+/// `Config::vertical_movespeed` for the up and down keys if set
+fn movement_velocity(
+    transform: &Transform,
+    keys: &[KeyCode],
+    config: &Config,
+    pressed: impl Fn(KeyCode) -> bool + Copy,
+    speed: f32,
+) -> Vec3 {
+    let v = movement_input(transform, keys, config, pressed);
+    let vertical_movespeed = match config.vertical_movespeed {
+        Some(vertical_movespeed) => vertical_movespeed,
+        None => return v.normalize_or_zero() * speed,
+    };
+
+    // the up and down keys are normalized separately from the others, so that
+    // they move at their own speed regardless of which other keys are held
+    let mut horizontal_config = *config;
+    horizontal_config.key_bindings.up = None;
+    horizontal_config.key_bindings.down = None;
+    let horizontal = movement_input(transform, keys, &horizontal_config, pressed);
+    let vertical = v - horizontal;
+
+    // sprinting and precision movement apply to the vertical speed as well
+    let scale = speed_multiplier(config, pressed);
+    horizontal.normalize_or_zero() * speed
+        + vertical.normalize_or_zero() * vertical_movespeed * scale
+}
+
+/// Moves `transform` by one step of `step` for every movement key pressed in
+/// `keys_just_pressed`, while the keys for which `pressed` returns true are held.
+/// This is the movement used by `MovementMode::Step`. Returns the sides of the
+/// bounds the movement was clamped against, like `apply_movement`.
+pub fn apply_step_movement(
+    transform: &mut Transform,
+    keys_just_pressed: &[KeyCode],
+    pressed: impl Fn(KeyCode) -> bool + Copy,
+    config: &Config,
+    step: f32,
+) -> Vec3 {
+    let v = movement_input(transform, keys_just_pressed, config, pressed);
+
+    let translation = transform.translation + v * step;
+    move_within_bounds(transform, translation, config)
+}
+
+/// The forward, right and up directions the camera moves in
+fn movement_axes(transform: &Transform, config: &Config) -> (Vec3, Vec3, Vec3) {
+    let up = up_basis(config, transform.translation) * Vec3::Y;
+    match config.rail_direction {
+        Some(rail) => {
+            let forward = rail.normalize_or_zero();
+            (forward, forward.cross(up).normalize_or_zero(), up)
+        }
+        None if config.six_dof => (
+            config.handedness.forward(transform),
+            transform.right(),
+            transform.up(),
+        ),
+        None => (config.handedness.forward(transform), transform.right(), up),
+    }
+}
+
+/// The sum of the directions of the movement keys in `keys`, given the keys
+/// for which `pressed` returns true for the purpose of modifiers
+fn movement_input(
+    transform: &Transform,
+    keys: &[KeyCode],
+    config: &Config,
+    pressed: impl Fn(KeyCode) -> bool + Copy,
+) -> Vec3 {
+    let bindings = &config.key_bindings;
+    let mut v = Vec3::ZERO;
+
+    let (forward, right, up) = movement_axes(transform, config);
+
+    for &key in keys {
+        match key {
+            x if is_bound(bindings.forward, x, pressed) => v += forward,
+            x if is_bound(bindings.back, x, pressed) => v -= forward,
+            x if is_bound(bindings.left, x, pressed) => v -= right,
+            x if is_bound(bindings.right, x, pressed) => v += right,
+            x if is_bound(bindings.up, x, pressed) => v += up,
+            x if is_bound(bindings.down, x, pressed) => v -= up,
+
+            _ => (),
+        }
+    }
+
+    if held_in(bindings.lock_vertical, pressed) {
+        v -= up * v.dot(up);
+    }
+
+    v
+}
+
+/// Wraps the camera around to the opposite side of `Config::wrap_bounds` on
+/// every axis it has moved out on
+fn wrap_around(transform: &mut Transform, config: &Config) {
+    let (min, max) = match config.wrap_bounds {
+        Some(wrap_bounds) => wrap_bounds,
+        None => return,
+    };
+
+    let wrap = |value: f32, min: f32, max: f32| {
+        if min < max && !(min..=max).contains(&value) {
+            min + (value - min).rem_euclid(max - min)
+        } else {
+            value
+        }
+    };
+    let t = transform.translation;
+    transform.translation = Vec3::new(
+        wrap(t.x, min.x, max.x),
+        wrap(t.y, min.y, max.y),
+        wrap(t.z, min.z, max.z),
+    );
+}
+
+/// Moves `transform` to `translation`, clamped to `config.bounds`. Returns the
+/// sides of the bounds the movement was clamped against.
+fn move_within_bounds(transform: &mut Transform, translation: Vec3, config: &Config) -> Vec3 {
+    match config.bounds {
+        Some((min, max)) => {
+            let clamped = translation.clamp(min, max);
+            transform.translation = clamped;
+
+            let side = |overshoot: f32| {
+                if overshoot > 0.0 {
+                    1.0
+                } else if overshoot < 0.0 {
+                    -1.0
+                } else {
+                    0.0
+                }
+            };
+            let overshoot = translation - clamped;
+            Vec3::new(side(overshoot.x), side(overshoot.y), side(overshoot.z))
+        }
+        None => {
+            transform.translation = translation;
+            Vec3::ZERO
+        }
+    }
+}
+
+/// Whether `key` triggers `binding`, given the keys for which `pressed` returns
+/// true. An unbound action never matches any key.
+fn is_bound(binding: Option<Binding>, key: KeyCode, pressed: impl Fn(KeyCode) -> bool) -> bool {
+    binding
+        .filter(|binding| binding.enabled)
+        .map_or(false, |binding| {
+            binding.key == key && binding.modifiers.held(pressed)
+        })
+}
+
+/// Whether `binding` is held, given the keys for which `pressed` returns true
+fn held_in(binding: Option<Binding>, pressed: impl Fn(KeyCode) -> bool) -> bool {
+    binding
+        .filter(|binding| binding.enabled)
+        .map_or(false, |binding| {
+            pressed(binding.key) && binding.modifiers.held(pressed)
+        })
+}
+
+/// Whether `binding` is currently held
+fn is_held(binding: Option<Binding>, keys: &Input<KeyCode>) -> bool {
+    held_in(binding, |key| keys.pressed(key))
+}
+
+/// Remembers the keys pressed this frame until the cameras next advance. This
+/// runs every frame, even when no step of a fixed timestep does.
+fn latch_presses(
+    keys: Res<Input<KeyCode>>,
+    windows: Res<Windows>,
+    cursor: Res<CursorControl>,
+    mut presses: ResMut<PendingPresses>,
+) {
+    if !cursor.is_locked(&windows) {
+        return;
+    }
+    for key in keys.get_just_pressed() {
+        if !presses.0.contains(key) {
+            presses.0.push(*key);
+        }
+    }
+}
+
+/// Forgets the presses which the cameras have just handled, so that the next
+/// step of the same frame doesn't handle them again
+fn consume_presses(mut presses: ResMut<PendingPresses>) {
+    presses.0.clear();
+}
+
+/// Marks the first camera as active if no camera is and
+/// `Config::control_all_cameras` is disabled
+fn ensure_active_camera(
+    mut cmd: Commands,
+    config: Res<Config>,
+    mut next_order: Local<u64>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    new: Query<Entity, (With<FpsCam>, Without<SpawnOrder>)>,
+    q: Query<(Entity, &SpawnOrder), With<FpsCam>>,
+) {
+    // neither the order of the query nor of entities follows the order of
+    // spawning, as bevy reuses the ids of despawned entities, so cameras are
+    // numbered as they appear. Cameras appearing in the same frame are
+    // numbered by id.
+    let mut new: Vec<Entity> = new.iter().collect();
+    new.sort_by_key(|entity| entity.id());
+    let first_new = new.first().map(|&entity| (SpawnOrder(*next_order), entity));
+    for entity in new {
+        cmd.entity(entity).insert(SpawnOrder(*next_order));
+        *next_order += 1;
+    }
+
+    if config.control_all_cameras || !active.is_empty() {
+        return;
+    }
+    let first = q
+        .iter()
+        .map(|(entity, order)| (*order, entity))
+        .min()
+        .or(first_new);
+    if let Some((_, first)) = first {
+        cmd.entity(first).insert(ActiveFpsCam);
+    }
+}
+
+/// Applies `RebindAction` events to `Config::key_bindings`
+fn rebind_actions(mut config: ResMut<Config>, mut events: EventReader<RebindAction>) {
+    for ev in events.iter() {
+        *config.key_bindings.binding_mut(ev.action) = Some(ev.key.into());
+    }
+}
+
+/// Toggles the `LookAxisLocks` with their bindings
+fn toggle_look_locks(
+    keys: Res<Input<KeyCode>>,
+    config: Res<Config>,
+    windows: Res<Windows>,
+    cursor: Res<CursorControl>,
+    presses: Res<PendingPresses>,
+    mut locks: ResMut<LookAxisLocks>,
+) {
+    if !cursor.is_locked(&windows) {
+        return;
+    }
+    if presses.just_pressed(config.key_bindings.lock_pitch, &keys) {
+        locks.pitch = !locks.pitch;
+    }
+    if presses.just_pressed(config.key_bindings.lock_yaw, &keys) {
+        locks.yaw = !locks.yaw;
+    }
+}
+
+/// Handles camera looking, only when the cursor is locked
+fn camera_look(
+    keys: Res<Input<KeyCode>>,
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    windows: Res<Windows>,
+    cursor: Res<CursorControl>,
+    locks: Res<LookAxisLocks>,
+    mut motion: ResMut<LookMotion>,
+    mut looked: EventWriter<FpsCamLooked>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    mut q: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut FpsCam,
+            &mut LookState,
+            Option<&ActiveFpsCam>,
+            Option<&PlayerInput>,
+            Option<&Zoomed>,
+            Option<&SensitivityScale>,
+        ),
+        ManualControl,
+    >,
+) {
+    let config = config.with_reduced_motion();
+    // the motion is taken once up front so that every controlled camera
+    // receives the same motion
+    let mut delta = std::mem::take(&mut motion.0);
+    if locks.yaw {
+        delta.x = 0.0;
+    }
+    if locks.pitch {
+        delta.y = 0.0;
+    }
+    if config.dpi_independent {
+        if let Some(window) = windows.get_primary() {
+            delta *= dpi_scale(window.physical_height());
+        }
+    }
+    let locked = cursor.is_locked(&windows);
+    let free_look = locked && is_held(config.key_bindings.free_look, &keys);
+    let look_behind = locked && is_held(config.key_bindings.look_behind, &keys);
+
+    let any_active = !active.is_empty();
+    for (entity, mut transform, mut fpscam, mut look, is_active, input, zoomed, scale) in
+        q.iter_mut()
+    {
+        let controlled = is_controlled(any_active, is_active) && uses_shared_input(input);
+        // only the view is turned, so the stored yaw is untouched and the
+        // camera faces exactly the same way again once the key is released
+        look.look_behind = look_behind && controlled;
+        if !controlled {
+            continue;
+        }
+
+        if free_look {
+            look.free_look_return = None;
+        } else if look.free_look_yaw != 0.0 {
+            let (start, elapsed) = look.free_look_return.unwrap_or((look.free_look_yaw, 0.0));
+            let elapsed = elapsed + time.smoothing_delta;
+            let t = ease(progress(elapsed, config.free_look_return_time));
+
+            look.free_look_yaw = start * (1.0 - t);
+            look.free_look_return = if t < 1.0 {
+                Some((start, elapsed))
+            } else {
+                None
+            };
+        }
+
+        if !locked {
+            look.pending = Vec2::ZERO;
+            look.angular_velocity = Vec2::ZERO;
+            continue;
+        }
+
+        let sensitivity = match zoomed {
+            Some(zoomed) => match config
+                .ads_sensitivity
+                .filter(|_| zoomed.ads)
+                .or(config.zoom_sensitivity)
+            {
+                Some(zoom_sensitivity) => {
+                    let amount = ease(zoomed.progress);
+                    config.sensitivity + (zoom_sensitivity - config.sensitivity) * amount
+                }
+                None if config.zoom_scales_sensitivity => config.sensitivity * zoomed.ratio,
+                None => config.sensitivity,
+            },
+            None => config.sensitivity,
+        } * scale.map_or(1.0, |scale| scale.0);
+
+        // the ramp starts over as soon as a frame passes without any motion
+        let ramp = if delta != Vec2::ZERO {
+            let ramp = (config.sensitivity_ramp)(look.motion_time);
+            look.motion_time += time.delta;
+            ramp
+        } else {
+            look.motion_time = 0.0;
+            1.0
+        };
+
+        look.pending += sensitivity * ramp * delta;
+
+        let mut step = if config.look_smoothing > 0.0 {
+            if look.pending.length() < config.look_smoothing_epsilon {
+                // residual motion is dropped so that the camera doesn't creep
+                // while the mouse is idle
+                look.pending = Vec2::ZERO;
+            }
+            look.pending * (1.0 - (-time.smoothing_delta / config.look_smoothing).exp())
+        } else {
+            look.pending
+        };
+
+        look.pending -= step;
+
+        if let Some(friction) = config.look_friction {
+            if step != Vec2::ZERO {
+                if time.delta > 0.0 {
+                    look.angular_velocity = step / time.delta;
+                }
+            } else {
+                look.angular_velocity *= (-friction * time.delta).exp();
+                step = look.angular_velocity * time.delta;
+                if step.length() < config.look_smoothing_epsilon {
+                    look.angular_velocity = Vec2::ZERO;
+                    step = Vec2::ZERO;
+                }
+            }
+        }
+
+        if step == Vec2::ZERO {
+            continue;
+        }
+        look.recenter = None;
+
+        // any pitch beyond the speed limit is discarded rather than applied
+        // later, so the camera doesn't keep drifting after the mouse stops
+        if let Some(max_pitch_speed) = config.max_pitch_speed {
+            let max_step = max_pitch_speed * time.delta;
+            step.y = step.y.clamp(-max_step, max_step);
+        }
+
+        // free look turns the view through the view offset, leaving the
+        // logical yaw which determines the direction of movement untouched
+        let yaw = if free_look {
+            look.free_look_yaw -= step.x;
+            0.0
+        } else {
+            -step.x
+        };
+        turn(&mut transform, &mut fpscam, &config, yaw, -step.y);
+
+        looked.send(FpsCamLooked {
+            entity,
+            yaw: fpscam.yaw,
+            pitch: fpscam.pitch,
+        });
+    }
+}
+
+/// What mouse motion is multiplied by with `Config::dpi_independent` in a
+/// window which is `physical_height` pixels tall. A window without a height,
+/// for example while minimized, leaves the motion unscaled.
+fn dpi_scale(physical_height: u32) -> f32 {
+    if physical_height > 0 {
+        REFERENCE_WINDOW_HEIGHT / physical_height as f32
+    } else {
+        1.0
+    }
+}
+
+/// Handles camera looking with the keyboard look bindings, only when the
+/// cursor is locked
+fn camera_keyboard_look(
+    keys: Res<Input<KeyCode>>,
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    windows: Res<Windows>,
+    cursor: Res<CursorControl>,
+    mut looked: EventWriter<FpsCamLooked>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    mut q: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut FpsCam,
+            &mut LookState,
+            Option<&ActiveFpsCam>,
+            Option<&PlayerInput>,
+        ),
+        ManualControl,
+    >,
+) {
+    let config = config.with_reduced_motion();
+    if !cursor.is_locked(&windows) {
+        return;
+    }
+
+    let held = |binding| is_held(binding, &keys);
+    let bindings = &config.key_bindings;
+
+    let mut delta = Vec2::ZERO;
+    if held(bindings.look_left) {
+        delta.x += 1.0;
+    }
+    if held(bindings.look_right) {
+        delta.x -= 1.0;
+    }
+    if held(bindings.look_up) {
+        delta.y += 1.0;
+    }
+    if held(bindings.look_down) {
+        delta.y -= 1.0;
+    }
+
+    if delta == Vec2::ZERO {
+        return;
+    }
+    delta *= config.keyboard_look_speed * time.delta;
+
+    let any_active = !active.is_empty();
+    for (entity, mut transform, mut fpscam, mut look, is_active, input) in q.iter_mut() {
+        if !is_controlled(any_active, is_active) || !uses_shared_input(input) {
+            continue;
+        }
+
+        look.recenter = None;
+
+        turn(&mut transform, &mut fpscam, &config, delta.x, delta.y);
+
+        looked.send(FpsCamLooked {
+            entity,
+            yaw: fpscam.yaw,
+            pitch: fpscam.pitch,
+        });
+    }
+}
+
+/// Handles smoothly levelling the pitch when the recenter key is pressed, or
+/// turning it as close to level as the pitch limits allow. Any look input
+/// cancels an ongoing recenter.
+fn camera_recenter_pitch(
+    keys: Res<Input<KeyCode>>,
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    windows: Res<Windows>,
+    cursor: Res<CursorControl>,
+    presses: Res<PendingPresses>,
+    mut looked: EventWriter<FpsCamLooked>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    mut q: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut FpsCam,
+            &mut LookState,
+            Option<&ActiveFpsCam>,
+            Option<&PlayerInput>,
+        ),
+        ManualControl,
+    >,
+) {
+    let pressed = cursor.is_locked(&windows)
+        && presses.just_pressed(config.key_bindings.recenter_pitch, &keys);
+
+    let any_active = !active.is_empty();
+    for (entity, mut transform, mut fpscam, mut look, is_active, input) in q.iter_mut() {
+        if !is_controlled(any_active, is_active) || !uses_shared_input(input) {
+            continue;
+        }
+
+        if pressed {
+            look.recenter = Some((fpscam.pitch, 0.0));
+        }
+
+        if let Some((start, elapsed)) = look.recenter {
+            let elapsed = elapsed + time.smoothing_delta;
+            let t = ease(progress(elapsed, config.recenter_duration));
+
+            let level = limit_pitch(0.0, &config);
+            fpscam.pitch = start * (1.0 - t) + level * t;
+            look.recenter = if t < 1.0 {
+                Some((start, elapsed))
+            } else {
+                None
+            };
+
+            transform.rotation =
+                up_basis(&config, transform.translation) * look_rotation(fpscam.yaw, fpscam.pitch);
+
+            looked.send(FpsCamLooked {
+                entity,
+                yaw: fpscam.yaw,
+                pitch: fpscam.pitch,
+            });
+        }
+    }
+}
+
+/// Turns a camera by `yaw` and `pitch` radians, either around the world up or,
+/// in six degrees of freedom mode, relative to its current orientation
+fn turn(transform: &mut Transform, fpscam: &mut FpsCam, config: &Config, yaw: f32, pitch: f32) {
+    let basis = up_basis(config, transform.translation);
+
+    if config.six_dof {
+        transform.rotation = (transform.rotation
+            * Quat::from_axis_angle(Vec3::Y, yaw)
+            * Quat::from_axis_angle(Vec3::X, pitch))
+        .normalize();
+
+        let (yaw, pitch) = yaw_pitch_from_rotation(basis.inverse() * transform.rotation);
+        fpscam.yaw = yaw;
+        fpscam.pitch = pitch;
+    } else {
+        fpscam.yaw = clamp_yaw(fpscam.yaw + yaw, config);
+        fpscam.pitch = limit_pitch(fpscam.pitch + pitch, config);
+        transform.rotation = basis * look_rotation(fpscam.yaw, fpscam.pitch);
+    }
+}
+
+/// Limits the yaw to `config.yaw_limits`, if set
+fn clamp_yaw(yaw: f32, config: &Config) -> f32 {
+    use std::f32::consts::{PI, TAU};
+
+    match config.yaw_limits {
+        Some((min, max)) if min <= max => yaw.clamp(min, max),
+        // the arc wraps around from `min` through ±π to `max`, so the yaw is
+        // wrapped into the same range before comparing
+        Some((min, max)) => {
+            let yaw = (yaw + PI).rem_euclid(TAU) - PI;
+            if yaw >= min || yaw <= max {
+                yaw
+            } else if min - yaw < yaw - max {
+                min
+            } else {
+                max
+            }
+        }
+        None => yaw,
+    }
+}
+
+/// Limits the pitch to looking straight up or down
+fn clamp_pitch(pitch: f32) -> f32 {
+    pitch.clamp(-std::f32::consts::PI / 2.0, std::f32::consts::PI / 2.0)
+}
+
+/// Limits the pitch to `config.pitch_min` and `config.pitch_max`. The limits
+/// never go past straight up or down, where the yaw would flip over.
+fn limit_pitch(pitch: f32, config: &Config) -> f32 {
+    let min = clamp_pitch(config.pitch_min);
+    let max = clamp_pitch(config.pitch_max);
+    if min <= max {
+        pitch.clamp(min, max)
+    } else {
+        (min + max) / 2.0
+    }
+}
+
+/// The yaw and pitch of `rotation` for a camera at `translation`, limited to
+/// the configured limits, along with the rotation they compose to. Outside of
+/// six degrees of freedom mode, where the limits don't apply, a rotation which
+/// exceeds them is recomposed from the limited yaw and pitch.
+fn limit_rotation(rotation: Quat, translation: Vec3, config: &Config) -> (Quat, f32, f32) {
+    let basis = up_basis(config, translation);
+    let (yaw, pitch) = yaw_pitch_from_rotation(basis.inverse() * rotation);
+    if config.six_dof {
+        return (rotation, yaw, pitch);
+    }
+
+    let limited = (clamp_yaw(yaw, config), limit_pitch(pitch, config));
+    if limited == (yaw, pitch) {
+        (rotation, yaw, pitch)
+    } else {
+        let (yaw, pitch) = limited;
+        (basis * look_rotation(yaw, pitch), yaw, pitch)
+    }
+}
+
+/// Handles dashing forward when the dash key is pressed, and cancelling dashes
+/// when a movement key is pressed. Movement keys which were already held when
+/// the dash started don't cancel it.
+fn camera_dash(
+    mut cmd: Commands,
+    keys: Res<Input<KeyCode>>,
+    config: Res<Config>,
+    windows: Res<Windows>,
+    cursor: Res<CursorControl>,
+    presses: Res<PendingPresses>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    dashing: Query<(Entity, Option<&MoveTo>), With<Dashing>>,
+    q: Query<
+        (
+            Entity,
+            &Transform,
+            Option<&ActiveFpsCam>,
+            Option<&PlayerInput>,
+        ),
+        (With<FpsCam>, ManualControl),
+    >,
+) {
+    let locked = cursor.is_locked(&windows);
+    let bindings = &config.key_bindings;
+    let moving = locked
+        && [
+            bindings.forward,
+            bindings.back,
+            bindings.left,
+            bindings.right,
+            bindings.up,
+            bindings.down,
+        ]
+        .into_iter()
+        .any(|binding| presses.just_pressed(binding, &keys));
+
+    for (entity, move_to) in dashing.iter() {
+        if move_to.is_none() {
+            cmd.entity(entity).remove::<Dashing>();
+        } else if moving {
+            cmd.entity(entity).remove::<MoveTo>().remove::<Dashing>();
+        }
+    }
+
+    if !locked || !presses.just_pressed(bindings.dash, &keys) || !config.movement_enabled {
+        return;
+    }
+
+    let any_active = !active.is_empty();
+    for (entity, transform, is_active, input) in q.iter() {
+        if !is_controlled(any_active, is_active) || !uses_shared_input(input) {
+            continue;
+        }
+
+        let mut target = *transform;
+        move_within_bounds(
+            &mut target,
+            transform.translation + transform.forward() * config.dash_distance,
+            &config,
+        );
+
+        cmd.entity(entity)
+            .insert(MoveTo::new(target, config.dash_duration))
+            .insert(Dashing);
+    }
+}
+
+/// Handles rolling the camera in six degrees of freedom mode, and levelling it
+/// when the level key is pressed
+fn camera_roll(
+    mut cmd: Commands,
+    keys: Res<Input<KeyCode>>,
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    windows: Res<Windows>,
+    cursor: Res<CursorControl>,
+    presses: Res<PendingPresses>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    mut q: Query<
+        (
+            Entity,
+            &mut Transform,
+            Option<&ActiveFpsCam>,
+            Option<&PlayerInput>,
+        ),
+        (With<FpsCam>, ManualControl),
+    >,
+) {
+    if !cursor.is_locked(&windows) {
+        return;
+    }
+
+    let bindings = &config.key_bindings;
+    let mut roll = 0.0;
+    if config.six_dof {
+        if is_held(bindings.roll_left, &keys) {
+            roll += 1.0;
+        }
+        if is_held(bindings.roll_right, &keys) {
+            roll -= 1.0;
+        }
+    }
+    let level = presses.just_pressed(bindings.level, &keys);
+
+    let any_active = !active.is_empty();
+    for (entity, mut transform, is_active, input) in q.iter_mut() {
+        if !is_controlled(any_active, is_active) || !uses_shared_input(input) {
+            continue;
+        }
+
+        if roll != 0.0 {
+            transform.rotation = (transform.rotation
+                * Quat::from_axis_angle(Vec3::Z, roll * config.roll_speed * time.delta))
+            .normalize();
+        }
+
+        if level {
+            let basis = up_basis(&config, transform.translation);
+            let (yaw, pitch) = yaw_pitch_from_rotation(basis.inverse() * transform.rotation);
+            let target = Transform {
+                rotation: basis * look_rotation(yaw, pitch),
+                ..*transform
+            };
+            cmd.entity(entity)
+                .insert(MoveTo::new(target, config.recenter_duration));
+        }
+    }
+}
+
+/// Handles kicking the view of cameras on `RecoilKick` events, and recovering
+/// from the recoil
+fn camera_recoil(
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    mut kicks: EventReader<RecoilKick>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    mut q: Query<(&mut LookState, Option<&ActiveFpsCam>), With<FpsCam>>,
+) {
+    let kick = kicks.iter().fold(Vec2::ZERO, |acc, kick| {
+        acc + Vec2::new(kick.yaw, kick.pitch)
+    });
+
+    let any_active = !active.is_empty();
+    for (mut look, is_active) in q.iter_mut() {
+        if look.recoil != Vec2::ZERO {
+            let recovered = Vec2::new(
+                ease_towards(
+                    look.recoil.x,
+                    0.0,
+                    time.smoothing_delta,
+                    config.recoil_recovery_time,
+                ),
+                ease_towards(
+                    look.recoil.y,
+                    0.0,
+                    time.smoothing_delta,
+                    config.recoil_recovery_time,
+                ),
+            );
+            look.recoil = if recovered.length() < config.look_smoothing_epsilon {
+                Vec2::ZERO
+            } else {
+                recovered
+            };
+        }
+
+        if is_controlled(any_active, is_active) {
+            look.recoil += kick;
+        }
+    }
+}
+
+/// Handles crouching while the crouch key is held. The transition runs
+/// forwards or backwards from wherever it currently is, so crouching and
+/// standing up again quickly never snaps the view.
+fn camera_crouch(
+    keys: Res<Input<KeyCode>>,
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    windows: Res<Windows>,
+    cursor: Res<CursorControl>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    mut q: Query<(&mut ViewOffset, Option<&ActiveFpsCam>, Option<&PlayerInput>), ManualControl>,
+) {
+    let held = cursor.is_locked(&windows) && is_held(config.key_bindings.crouch, &keys);
+    let step = if config.crouch_transition_time > 0.0 {
+        time.smoothing_delta / config.crouch_transition_time
+    } else {
+        1.0
+    };
+
+    let any_active = !active.is_empty();
+    for (mut offset, is_active, input) in q.iter_mut() {
+        let crouch = held && is_controlled(any_active, is_active) && uses_shared_input(input);
+        offset.crouch_progress = if crouch {
+            (offset.crouch_progress + step).min(1.0)
+        } else {
+            (offset.crouch_progress - step).max(0.0)
+        };
+    }
+}
+
+/// Handles zooming in while the zoom key is held, and back out once it is
+/// released. Cameras which are driven automatically zoom back out as well.
+fn camera_zoom(
+    mut cmd: Commands,
+    keys: Res<Input<KeyCode>>,
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    windows: Res<Windows>,
+    cursor: Res<CursorControl>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    manual: Query<(), (With<FpsCam>, ManualControl)>,
+    mut q: Query<
+        (
+            Entity,
+            Option<&mut PerspectiveProjection>,
+            Option<&mut OrthographicProjection>,
+            Option<&ActiveFpsCam>,
+            Option<&PlayerInput>,
+            Option<&mut Zoomed>,
+        ),
+        With<FpsCam>,
+    >,
+) {
+    let locked = cursor.is_locked(&windows);
+    let zoom_held = locked && is_held(config.key_bindings.zoom, &keys);
+    let ads_held = locked && is_held(config.key_bindings.ads, &keys);
+    let any_active = !active.is_empty();
+    for (entity, perspective, orthographic, is_active, input, zoomed) in q.iter_mut() {
+        let zoom = (zoom_held || ads_held)
+            && manual.get(entity).is_ok()
+            && is_controlled(any_active, is_active)
+            && uses_shared_input(input);
+
+        let (base, progress, ads) = match &zoomed {
+            Some(zoomed) => (zoomed.base, zoomed.progress, zoomed.ads),
+            None if zoom => match (&perspective, &orthographic) {
+                (Some(projection), _) => (projection.fov, 0.0, !zoom_held),
+                (None, Some(projection)) => (projection.scale, 0.0, !zoom_held),
+                (None, None) => continue,
+            },
+            None => continue,
+        };
+        let target_fov = if ads { config.ads_fov } else { config.zoom_fov };
+
+        let step = if config.zoom_time > 0.0 {
+            time.smoothing_delta / config.zoom_time
+        } else {
+            1.0
+        };
+        // the transition runs forwards or backwards from wherever it currently
+        // is, so pressing or releasing the key mid-transition reverses it
+        // smoothly instead of jumping to either end
+        let progress = if zoom {
+            (progress + step).min(1.0)
+        } else {
+            (progress - step).max(0.0)
+        };
+        let amount = ease(progress);
+
+        let ratio = if let Some(mut projection) = perspective {
+            projection.fov = base + (target_fov - base) * amount;
+            (projection.fov / 2.0).tan() / (base / 2.0).tan()
+        } else if let Some(mut projection) = orthographic {
+            projection.scale = base * (1.0 + (config.zoom_scale - 1.0) * amount);
+            projection.scale / base
+        } else {
+            1.0
+        };
+
+        match zoomed {
+            Some(mut zoomed) if progress > 0.0 => {
+                zoomed.progress = progress;
+                zoomed.ratio = ratio;
+            }
+            Some(_) => {
+                cmd.entity(entity).remove::<Zoomed>();
+            }
+            None => {
+                cmd.entity(entity).insert(Zoomed {
+                    base,
+                    progress,
+                    ratio,
+                    ads,
+                });
+            }
+        }
+    }
+}
+
+/// The rotation of a camera with the given yaw and pitch
+fn look_rotation(yaw: f32, pitch: f32) -> Quat {
+    Quat::from_axis_angle(Vec3::Y, yaw) * Quat::from_axis_angle(Vec3::X, pitch)
+}
+
+/// The rotation from the frame in which yaw and pitch are measured to world
+/// space, for a camera at `translation`. This is the identity unless
+/// `config.gravity_source` is set.
+fn up_basis(config: &Config, translation: Vec3) -> Quat {
+    let up = match config.gravity_source {
+        Some(center) => (translation - center).normalize_or_zero(),
+        None => Vec3::ZERO,
+    };
+
+    if up == Vec3::ZERO {
+        Quat::IDENTITY
+    } else {
+        Quat::from_rotation_arc(Vec3::Y, up)
+    }
+}
+
+/// Keeps a camera which moved from `from` upright as up changes along the way,
+/// by turning its view with the change in up. Unlike recomposing the rotation
+/// from the yaw and pitch, this doesn't turn the heading where the up basis
+/// changes quickly, close to the point opposite +Y. The yaw and pitch are
+/// updated to match the new up basis.
+fn transport_view(transform: &mut Transform, fpscam: &mut FpsCam, config: &Config, from: Vec3) {
+    let old_up = up_basis(config, from) * Vec3::Y;
+    let basis = up_basis(config, transform.translation);
+    let rotation = Quat::from_rotation_arc(old_up, basis * Vec3::Y) * transform.rotation;
+
+    let (yaw, pitch) = yaw_pitch_from_rotation(basis.inverse() * rotation);
+    fpscam.yaw = yaw;
+    fpscam.pitch = pitch;
+    transform.rotation = basis * look_rotation(yaw, pitch);
+}
+
+/// Whether a camera is controlled by the shared keyboard and mouse, rather than
+/// by its own input device
+fn uses_shared_input(input: Option<&PlayerInput>) -> bool {
+    !matches!(input, Some(PlayerInput::Gamepad(_)))
+}
+
+/// Whether a camera should respond to input, given whether any camera is
+/// marked as active
+fn is_controlled(any_active: bool, is_active: Option<&ActiveFpsCam>) -> bool {
+    !any_active || is_active.is_some()
+}
+
+/// Handles animating cameras with a `MoveTo` component
+fn camera_move_to(
+    mut cmd: Commands,
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    mut q: Query<(Entity, &mut Transform, &mut FpsCam, &mut MoveTo)>,
+) {
+    for (entity, mut transform, mut fpscam, mut move_to) in q.iter_mut() {
+        let before = ease(progress(move_to.elapsed, move_to.duration));
+        move_to.elapsed += time.smoothing_delta;
+        let after = ease(progress(move_to.elapsed, move_to.duration));
+
+        // a target beyond the look limits is eased into at the limits, so the
+        // view doesn't snap once looking around resumes
+        let target = move_to.target.translation;
+        let (rotation, yaw, pitch) = limit_rotation(move_to.target.rotation, target, &config);
+        if after >= 1.0 {
+            transform.translation = target;
+            transform.rotation = rotation;
+            fpscam.yaw = yaw;
+            fpscam.pitch = pitch;
+
+            cmd.entity(entity).remove::<MoveTo>();
+        } else {
+            // the remaining distance is covered proportionally, so the eased
+            // path is followed without having to remember the start transform
+            let s = (after - before) / (1.0 - before);
+            transform.translation = transform.translation.lerp(target, s);
+            transform.rotation = transform.rotation.slerp(rotation, s);
+        }
+    }
+}
+
+/// Handles moving cameras with an `Orbit` component
+fn camera_orbit(
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    mut q: Query<(&mut Transform, &mut FpsCam, &Orbit)>,
+) {
+    for (mut transform, mut fpscam, orbit) in q.iter_mut() {
+        // continue from wherever the camera currently is on the circle
+        let offset = transform.translation - orbit.center;
+        let angle = offset.x.atan2(offset.z) + orbit.speed * time.delta;
+
+        transform.translation = orbit.center
+            + Vec3::new(
+                angle.sin() * orbit.radius,
+                orbit.height,
+                angle.cos() * orbit.radius,
+            );
+
+        fpscam.yaw = clamp_yaw(angle, &config);
+        fpscam.pitch = limit_pitch((-orbit.height).atan2(orbit.radius), &config);
+        transform.rotation = look_rotation(fpscam.yaw, fpscam.pitch);
+    }
+}
+
+/// Keeps `LastInputDevice` up to date
+fn track_input_device(
+    mut device: ResMut<LastInputDevice>,
+    keys: Res<Input<KeyCode>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut mouse_buttons: EventReader<MouseButtonInput>,
+    mut gamepad_events: EventReader<GamepadEvent>,
+) {
+    let mut used = device.0;
+
+    if keys.get_just_pressed().next().is_some()
+        || mouse_motion.iter().count() + mouse_buttons.iter().count() > 0
+    {
+        used = PlayerInput::KeyboardMouse;
+    }
+
+    for GamepadEvent(gamepad, event) in gamepad_events.iter() {
+        match event {
+            GamepadEventType::AxisChanged(..) | GamepadEventType::ButtonChanged(..) => {
+                used = PlayerInput::Gamepad(*gamepad);
+            }
+            GamepadEventType::Disconnected if used == PlayerInput::Gamepad(*gamepad) => {
+                used = PlayerInput::KeyboardMouse;
+            }
+            _ => (),
+        }
+    }
+
+    if device.0 != used {
+        device.0 = used;
+    }
+}
+
+/// Collects the look motion and joystick deflection from touches, if enabled
+fn read_touch(
+    config: Res<Config>,
+    windows: Res<Windows>,
+    touches: Res<Touches>,
+    mut motion: ResMut<LookMotion>,
+    mut movement: ResMut<TouchMovement>,
+) {
+    movement.0 = Vec2::ZERO;
+    if !config.touch_enabled {
+        return;
+    }
+
+    let split = match windows.get_primary() {
+        Some(window) => window.width() * config.touch_look_split,
+        None => return,
+    };
+
+    for touch in touches.iter() {
+        if touch.start_position().x >= split {
+            // touch positions grow upwards, unlike mouse motion
+            let delta = touch.delta() * Vec2::new(1.0, -1.0);
+            motion.0 += delta * config.touch_sensitivity;
+        } else if movement.0 == Vec2::ZERO && config.touch_joystick_radius > 0.0 {
+            let offset = touch.position() - touch.start_position();
+            movement.0 = (offset / config.touch_joystick_radius).clamp_length_max(1.0);
+        }
+    }
+}
+
+/// Collects the motion from `MouseMotion` events, if enabled
+fn read_mouse_motion(
+    config: Res<Config>,
+    windows: Res<Windows>,
+    cursor: Res<CursorControl>,
+    mut events: EventReader<MouseMotion>,
+    mut resized: EventReader<WindowResized>,
+    mut moved: EventReader<WindowMoved>,
+    mut motion: ResMut<LookMotion>,
+    mut suppressed_frames: Local<u32>,
+    mut was_locked: Local<bool>,
+) {
+    let delta = events.iter().fold(Vec2::ZERO, |acc, ev| {
+        acc + outside_deadzone(ev.delta, config.motion_deadzone)
+    });
+
+    // both readers are drained every frame so that old events don't trigger
+    // suppression once it is enabled
+    let window_changed = resized.iter().count() + moved.iter().count() > 0;
+    if window_changed && config.suppress_look_on_resize {
+        *suppressed_frames = RESIZE_SUPPRESS_FRAMES;
+    }
+
+    // locking may warp the cursor back from where it was recentered to
+    let locked = cursor.is_locked(&windows);
+    if locked && !*was_locked && config.recenter_smoothly {
+        *suppressed_frames = RELOCK_SUPPRESS_FRAMES;
+    }
+    *was_locked = locked;
+    if *suppressed_frames > 0 {
+        *suppressed_frames -= 1;
+        return;
+    }
+
+    if config.use_mouse_motion {
+        motion.0 += delta;
+    }
+}
+
+/// Collects the motion from a custom motion source
+fn read_motion<M: MotionSource>(
+    config: Res<Config>,
+    mut events: EventReader<M>,
+    mut motion: ResMut<LookMotion>,
+) {
+    for ev in events.iter() {
+        motion.0 += outside_deadzone(ev.motion_delta(), config.motion_deadzone);
+    }
+}
+
+/// Discards the motion of a single event if it is smaller than `deadzone`.
+/// This is applied per event rather than to the total motion of a frame, so
+/// that slow but deliberate movements still register.
+fn outside_deadzone(delta: Vec2, deadzone: f32) -> Vec2 {
+    if delta.length() < deadzone {
+        Vec2::ZERO
+    } else {
+        delta
+    }
+}
+
+/// Re-derives the yaw and pitch of cameras from their transforms if enabled,
+/// so that rotations applied by other systems are respected
+fn sync_from_transform(config: Res<Config>, mut q: Query<(&Transform, &mut FpsCam)>) {
+    if !config.sync_from_transform {
+        return;
+    }
+
+    for (transform, mut fpscam) in q.iter_mut() {
+        let basis = up_basis(&config, transform.translation);
+        let (yaw, pitch) = yaw_pitch_from_rotation(basis.inverse() * transform.rotation);
+        if (yaw, pitch) != (fpscam.yaw, fpscam.pitch) {
+            fpscam.yaw = yaw;
+            fpscam.pitch = pitch;
+        }
+    }
+}
+
+/// Recomposes the rotation of cameras from their yaw and pitch if enabled, so
+/// that yaw and pitch written by other systems are applied
+fn apply_yaw_pitch(config: Res<Config>, mut q: Query<(&mut Transform, &FpsCam), ManualControl>) {
+    if !config.apply_yaw_pitch {
+        return;
+    }
+
+    for (mut transform, fpscam) in q.iter_mut() {
+        transform.rotation =
+            up_basis(&config, transform.translation) * look_rotation(fpscam.yaw, fpscam.pitch);
+    }
+}
+
+/// Captures keyframes for cameras with a `PathRecorder`
+fn record_path(time: Res<CameraTime>, mut q: Query<(&Transform, &mut PathRecorder), With<FpsCam>>) {
+    for (transform, mut recorder) in q.iter_mut() {
+        if recorder.elapsed >= recorder.next_keyframe {
+            let elapsed = recorder.elapsed;
+            recorder.path.keyframes.push((elapsed, *transform));
+            recorder.next_keyframe += recorder.interval.max(f32::EPSILON);
+        }
+        recorder.elapsed += time.delta;
+    }
+}
+
+/// Handles driving cameras with a `PathPlayback` component
+fn camera_playback(
+    mut cmd: Commands,
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    mut q: Query<(Entity, &mut Transform, &mut FpsCam, &mut PathPlayback)>,
+) {
+    for (entity, mut transform, mut fpscam, mut playback) in q.iter_mut() {
+        playback.elapsed += time.delta;
+
+        if let Some(sample) = playback.path.sample(playback.elapsed) {
+            let (rotation, yaw, pitch) =
+                limit_rotation(sample.rotation, sample.translation, &config);
+            transform.translation = sample.translation;
+            transform.rotation = rotation;
+            fpscam.yaw = yaw;
+            fpscam.pitch = pitch;
+        }
+
+        if playback.elapsed >= playback.path.duration() {
+            cmd.entity(entity).remove::<PathPlayback>();
+        }
+    }
+}
+
+/// Keeps cameras above the terrain described by `GroundHeight`, if present
+fn clamp_to_ground(
+    ground: Option<Res<GroundHeight>>,
+    mut q: Query<&mut Transform, (With<FpsCam>, ManualControl)>,
+) {
+    let ground = match ground {
+        Some(ground) => ground,
+        None => return,
+    };
+
+    for mut transform in q.iter_mut() {
+        let position = Vec2::new(transform.translation.x, transform.translation.z);
+        let height = (ground.height)(position) + ground.offset;
+        if ground.snap || transform.translation.y < height {
+            transform.translation.y = height;
+        }
+    }
+}
+
+/// Starts blending a camera which just became active from the pose of the
+/// previously active camera, if enabled
+fn blend_camera_switch(
+    mut cmd: Commands,
+    config: Res<Config>,
+    mut previous: ResMut<PreviousActiveCamera>,
+    mut q: Query<
+        (Entity, &mut Transform, ChangeTrackers<ActiveFpsCam>),
+        (With<FpsCam>, With<ActiveFpsCam>),
+    >,
+) {
+    let mut current = None;
+    for (entity, mut transform, tracker) in q.iter_mut() {
+        match previous.0 {
+            Some((last, from))
+                if tracker.is_added() && last != entity && config.switch_duration > 0.0 =>
+            {
+                let target = *transform;
+                *transform = from;
+                cmd.entity(entity)
+                    .insert(MoveTo::new(target, config.switch_duration));
+            }
+            _ => (),
+        }
+
+        current = current.or(Some((entity, *transform)));
+    }
+
+    if current.is_some() {
+        previous.0 = current;
+    }
+}
+
+/// Smoothly rotates any roll which was introduced by other systems back to
+/// level, if enabled. Cameras are left alone while they are rolled with the
+/// roll bindings.
+fn auto_level(
+    keys: Res<Input<KeyCode>>,
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    windows: Res<Windows>,
+    cursor: Res<CursorControl>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    mut q: Query<(&mut Transform, Option<&ActiveFpsCam>, Option<&PlayerInput>), With<FpsCam>>,
+) {
+    if config.auto_level_speed <= 0.0 {
+        return;
+    }
+
+    // matches when `camera_roll` rolls the cameras
+    let bindings = &config.key_bindings;
+    let rolling = config.six_dof
+        && cursor.is_locked(&windows)
+        && (is_held(bindings.roll_left, &keys) || is_held(bindings.roll_right, &keys));
+
+    let any_active = !active.is_empty();
+    for (mut transform, is_active, input) in q.iter_mut() {
+        if rolling && is_controlled(any_active, is_active) && uses_shared_input(input) {
+            continue;
+        }
+
+        let basis = up_basis(&config, transform.translation);
+        let (yaw, pitch) = yaw_pitch_from_rotation(basis.inverse() * transform.rotation);
+        let level = basis * look_rotation(yaw, pitch);
+
+        let angle = transform.rotation.angle_between(level);
+        if angle <= f32::EPSILON {
+            continue;
+        }
+
+        let max_step = config.auto_level_speed * time.delta;
+        transform.rotation = if angle <= max_step {
+            level
+        } else {
+            transform.rotation.slerp(level, max_step / angle)
+        };
+    }
+}
+
+/// Handles transitioning the sensitivity towards a `SensitivityTarget`
+fn update_sensitivity(
+    mut cmd: Commands,
+    time: Res<CameraTime>,
+    mut config: ResMut<Config>,
+    target: Option<ResMut<SensitivityTarget>>,
+) {
+    let mut target = match target {
+        Some(target) => target,
+        None => return,
+    };
+
+    let (start, elapsed) = target.start.unwrap_or((config.sensitivity, 0.0));
+    let elapsed = elapsed + time.smoothing_delta;
+    let t = ease(progress(elapsed, target.duration));
+
+    config.sensitivity = start + (target.target - start) * t;
+    if t < 1.0 {
+        target.start = Some((start, elapsed));
+    } else {
+        cmd.remove_resource::<SensitivityTarget>();
+    }
+}
+
+/// Determines the timestep for the camera this frame
+fn update_camera_time(
+    time: Res<Time>,
+    config: Res<Config>,
+    windows: Res<Windows>,
+    cursor: Res<CursorControl>,
+    mut camera_time: ResMut<CameraTime>,
+    mut paused: ResMut<TimePaused>,
+) {
+    let is_paused = config.pause_time_on_unlock && !cursor.is_locked(&windows);
+    if paused.0 != is_paused {
+        paused.0 = is_paused;
+    }
+
+    let now = Instant::now();
+    let real_delta = camera_time
+        .last_update
+        .map_or(0.0, |last| now.duration_since(last).as_secs_f32());
+    camera_time.last_update = Some(now);
+
+    let delta = if is_paused {
+        0.0
+    } else if let Some(step) = camera_time.fixed_step {
+        step
+    } else if config.use_real_time {
+        real_delta
+    } else {
+        time.delta_seconds()
+    };
+    camera_time.delta = config
+        .max_delta
+        .map_or(delta, |max_delta| delta.min(max_delta));
+    camera_time.smoothing_delta = camera_time.delta * config.camera_time_scale;
+}
+
+/// Adds the internal bookkeeping components to new cameras
+fn init_camera_state(mut cmd: Commands, q: Query<Entity, (With<FpsCam>, Without<ViewOffset>)>) {
+    for entity in q.iter() {
+        cmd.entity(entity)
+            .insert(ViewOffset::default())
+            .insert(LookState::default())
+            .insert(MovementState::default());
+    }
+}
+
+/// Restores the logical transform of cameras before they are controlled, and
+/// clears the movement of the previous frame
+fn reset_camera_state(mut q: Query<(&mut Transform, &mut ViewOffset, &mut MovementState)>) {
+    for (mut transform, mut offset, mut movement) in q.iter_mut() {
+        // a transform which was set externally is taken as the new logical
+        // transform as is
+        if offset.translation != Vec3::ZERO {
+            if transform.translation == offset.rendered.translation {
+                transform.translation -= offset.translation;
+            }
+            offset.translation = Vec3::ZERO;
+        }
+        if offset.rotation != Quat::IDENTITY {
+            if transform.rotation == offset.rendered.rotation {
+                transform.rotation *= offset.rotation.inverse();
+            }
+            offset.rotation = Quat::IDENTITY;
+        }
+        movement.velocity = Vec3::ZERO;
+    }
+}
+
+/// Offsets the rendered transform of cameras after they have been controlled
+fn apply_view_offset(
+    time: Res<CameraTime>,
+    config: Res<Config>,
+    mut q: Query<(
+        &mut Transform,
+        &FpsCam,
+        &mut ViewOffset,
+        &LookState,
+        &MovementState,
+        Option<&EyeOffset>,
+        Option<&mut CameraShake>,
+        Option<&Zoomed>,
+    )>,
+) {
+    let config = config.with_reduced_motion();
+    for (mut transform, fpscam, mut offset, look, movement, eye, shake, zoomed) in q.iter_mut() {
+        let basis = up_basis(&config, transform.translation);
+        let yaw_rotation = basis * Quat::from_axis_angle(Vec3::Y, fpscam.yaw);
+        let mut translation = Vec3::ZERO;
+
+        if let Some(eye) = eye {
+            translation += yaw_rotation * eye.0;
+        }
+
+        translation -=
+            basis * Vec3::Y * config.crouch_height * (config.crouch_easing)(offset.crouch_progress);
+
+        if let Some(zoomed) = zoomed.filter(|zoomed| zoomed.ads) {
+            translation += transform.rotation * config.ads_offset * ease(zoomed.progress);
+        }
+
+        let speed_fraction = |speed: f32| {
+            if config.movespeed > 0.0 {
+                (speed / config.movespeed).clamp(-1.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+
+        // lean into the direction of strafing, rolling back to level when not
+        // strafing
+        let strafe = speed_fraction(movement.velocity.dot(yaw_rotation * Vec3::X));
+        let target_tilt = -strafe * config.strafe_tilt_degrees.to_radians();
+        offset.tilt = ease_towards(
+            offset.tilt,
+            target_tilt,
+            time.smoothing_delta,
+            EFFECT_EASE_TIME,
+        );
+        let mut rotation = Quat::from_axis_angle(Vec3::Z, offset.tilt);
+
+        let mut view_yaw = look.free_look_yaw + look.recoil.x;
+        if look.look_behind {
+            view_yaw += config.look_behind_angle;
+        }
+        if view_yaw != 0.0 || look.recoil.y != 0.0 {
+            // turns the yaw underneath the pitch, as if free look and recoil
+            // had been added to the logical yaw and pitch
+            let view_pitch = limit_pitch(fpscam.pitch + look.recoil.y, &config);
+            rotation = Quat::from_axis_angle(Vec3::X, -fpscam.pitch)
+                * Quat::from_axis_angle(Vec3::Y, view_yaw)
+                * Quat::from_axis_angle(Vec3::X, view_pitch)
+                * rotation;
+        }
+
+        if config.head_bob_enabled {
+            let speed = speed_fraction(movement.velocity.length());
+            offset.bob_intensity = ease_towards(
+                offset.bob_intensity,
+                speed,
+                time.smoothing_delta,
+                EFFECT_EASE_TIME,
+            );
+            offset.bob_phase = (offset.bob_phase
+                + std::f32::consts::TAU * config.head_bob_frequency * speed * time.delta)
+                % std::f32::consts::TAU;
+
+            // the vertical bob runs at twice the frequency of the sideways
+            // sway, once for every step
+            let bob = offset.bob_intensity * config.head_bob_amplitude;
+            translation += basis * Vec3::Y * bob * (2.0 * offset.bob_phase).sin()
+                + yaw_rotation * Vec3::X * 0.5 * bob * offset.bob_phase.sin();
+        } else {
+            offset.bob_intensity = 0.0;
+        }
+
+        if let Some(mut shake) = shake.filter(|shake| shake.trauma > 0.0) {
+            offset.shake_time += time.delta * SHAKE_FREQUENCY;
+            let t = offset.shake_time;
+            let amount = shake.trauma * shake.trauma;
+
+            let angle = amount * config.shake_max_angle;
+            rotation = rotation
+                * Quat::from_axis_angle(Vec3::Y, angle * noise(config.effect_seed, 0, t))
+                * Quat::from_axis_angle(Vec3::X, angle * noise(config.effect_seed, 1, t))
+                * Quat::from_axis_angle(Vec3::Z, angle * noise(config.effect_seed, 2, t));
+
+            let distance = amount * config.shake_max_offset;
+            translation += transform.rotation
+                * Vec3::new(
+                    noise(config.effect_seed, 3, t),
+                    noise(config.effect_seed, 4, t),
+                    noise(config.effect_seed, 5, t),
+                )
+                * distance;
+
+            shake.trauma = (shake.trauma - config.shake_decay * time.delta).max(0.0);
+        }
+
+        if translation != Vec3::ZERO {
+            transform.translation += translation;
+            offset.translation = translation;
+        }
+        if rotation != Quat::IDENTITY {
+            transform.rotation *= rotation;
+            offset.rotation = rotation;
+        }
+        offset.rendered = *transform;
+    }
+}
+
+/// Smooth one dimensional value noise in the range [-1, 1]. Each `channel`
+/// gives an independent noise signal, and the same `seed` always produces the
+/// same noise.
+fn noise(seed: u32, channel: u32, t: f32) -> f32 {
+    let i = t.floor();
+    let a = hash(seed, channel, i as i32);
+    let b = hash(seed, channel, i as i32 + 1);
+    a + (b - a) * ease(t - i)
+}
+
+/// Hashes the inputs to a pseudo random value in the range [-1, 1]
+fn hash(seed: u32, channel: u32, i: i32) -> f32 {
+    let mut x = (i as u32).wrapping_mul(0x9e37_79b1)
+        ^ channel.wrapping_mul(0x85eb_ca6b)
+        ^ seed.wrapping_mul(0xc2b2_ae35);
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846c_a68b);
+    x ^= x >> 16;
+    x as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+/// Exponentially eases `current` towards `target` with the given time constant
+fn ease_towards(current: f32, target: f32, dt: f32, time_constant: f32) -> f32 {
+    current + (target - current) * (1.0 - (-dt / time_constant).exp())
+}
+
+fn progress(elapsed: f32, duration: f32) -> f32 {
+    if duration > 0.0 {
+        (elapsed / duration).clamp(0.0, 1.0)
+    } else {
+        1.0
+    }
+}
+
+/// Smoothstep easing
+fn ease(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Decomposes a rotation into the yaw and pitch used by `FpsCam`. The yaw is a
+/// rotation around the Y axis and the pitch a rotation around the local X axis
+/// applied after it, with the pitch in the range [-π/2, π/2]. Use this to keep
+/// an `FpsCam` consistent with a transform which was set externally. Any roll
+/// in the rotation is discarded, except when looking almost straight up or
+/// down, where yaw and roll can't be told apart and the rotation is assumed to
+/// have no roll.
+pub fn yaw_pitch_from_rotation(rotation: Quat) -> (f32, f32) {
+    let forward = rotation * -Vec3::Z;
+
+    // roll turns the view around the forward vector, so its heading is the
+    // yaw. When looking straight up or down it has no heading, but without
+    // roll the right vector stays horizontal and still determines the yaw
+    let yaw = if forward.x * forward.x + forward.z * forward.z > 1e-5 {
+        (-forward.x).atan2(-forward.z)
+    } else {
+        let right = rotation * Vec3::X;
+        (-right.z).atan2(right.x)
+    };
+    let pitch = forward.y.clamp(-1.0, 1.0).asin();
+
+    (yaw, pitch)
+}
+
+/// The ray through the screen position `cursor` of a perspective camera with
+/// the given transform and projection, as its origin and normalized direction.
+/// `cursor` is in pixels from the bottom left of a window of `window_size`, as
+/// returned by `Window::cursor_position`.
+pub fn screen_ray(
+    transform: &Transform,
+    projection: &PerspectiveProjection,
+    window_size: Vec2,
+    cursor: Vec2,
+) -> (Vec3, Vec3) {
+    let ndc = cursor / window_size * 2.0 - Vec2::ONE;
+    let half_height = (projection.fov * 0.5).tan();
+
+    let direction = Vec3::new(
+        ndc.x * half_height * projection.aspect_ratio,
+        ndc.y * half_height,
+        -1.0,
+    );
+
+    (
+        transform.translation,
+        (transform.rotation * direction).normalize(),
+    )
+}
+
+/// Where the ray through the screen position `cursor` hits the horizontal
+/// plane at height `y`, as described by `screen_ray`. Returns `None` if the
+/// ray points away from or along the plane.
+pub fn screen_to_plane(
+    transform: &Transform,
+    projection: &PerspectiveProjection,
+    window_size: Vec2,
+    cursor: Vec2,
+    y: f32,
+) -> Option<Vec3> {
+    let (origin, direction) = screen_ray(transform, projection, window_size, cursor);
+    if direction.y.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    let distance = (y - origin.y) / direction.y;
+    if distance < 0.0 {
+        return None;
+    }
+
+    Some(origin + direction * distance)
+}
+
+/// The world space corners of the view frustum of a perspective camera with
+/// the given transform and projection, between the `near` and `far` distances.
+/// The four corners of the near plane come first, followed by those of the far
+/// plane, each going counterclockwise from the bottom left as seen by the
+/// camera.
+pub fn frustum_corners(
+    transform: &Transform,
+    projection: &PerspectiveProjection,
+    near: f32,
+    far: f32,
+) -> [Vec3; 8] {
+    let half_height = (projection.fov * 0.5).tan();
+    let half_width = half_height * projection.aspect_ratio;
+
+    let corner = |x: f32, y: f32, distance: f32| {
+        *transform * (Vec3::new(x * half_width, y * half_height, -1.0) * distance)
+    };
+
+    [
+        corner(-1.0, -1.0, near),
+        corner(1.0, -1.0, near),
+        corner(1.0, 1.0, near),
+        corner(-1.0, 1.0, near),
+        corner(-1.0, -1.0, far),
+        corner(1.0, -1.0, far),
+        corner(1.0, 1.0, far),
+        corner(-1.0, 1.0, far),
+    ]
+}
+
+/// Handles matching the cursor lock state when the window gains or loses focus
+fn lock_on_focus(
+    config: Res<Config>,
+    mut windows: ResMut<Windows>,
+    mut cursor: ResMut<CursorControl>,
+    mut focus_events: EventReader<WindowFocused>,
+    mut initial_focus_seen: Local<bool>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    cursor_cams: CursorCameras,
+) {
+    let primary = windows.get_primary().map(|window| window.id());
+    let manage = manages_cursor(&active, &cursor_cams);
+    for ev in focus_events.iter() {
+        if manage && Some(ev.id) == primary {
+            // the window gaining focus when it is first opened would override
+            // the configured startup cursor state
+            if !*initial_focus_seen && ev.focused {
+                *initial_focus_seen = true;
+                continue;
+            }
+            *initial_focus_seen = true;
+
+            // in editor mode the cursor is only locked while the look button
+            // is held, which is never the case when the window gains focus
+            cursor.set_locked(&mut windows, ev.focused && !config.editor_mode);
+        }
+    }
+}
+
+/// Stops the cameras when the window loses focus, if
+/// `Config::stop_on_focus_loss` is enabled
+fn stop_on_focus_loss(
+    config: Res<Config>,
+    windows: Res<Windows>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut motion: ResMut<LookMotion>,
+    mut focus_events: EventReader<WindowFocused>,
+    mut q: Query<(&mut MovementState, &mut LookState)>,
+) {
+    let primary = windows.get_primary().map(|window| window.id());
+    let lost_focus = focus_events
+        .iter()
+        .any(|ev| !ev.focused && Some(ev.id) == primary);
+    if !lost_focus || !config.stop_on_focus_loss {
+        return;
+    }
+
+    let held: Vec<KeyCode> = keys.get_pressed().copied().collect();
+    for key in held {
+        keys.release(key);
+    }
+    motion.0 = Vec2::ZERO;
+
+    for (mut movement, mut look) in q.iter_mut() {
+        movement.speed = None;
+        movement.smoothed_velocity = Vec3::ZERO;
+        look.pending = Vec2::ZERO;
+        look.angular_velocity = Vec2::ZERO;
+    }
+}
+
+/// Whether any of the controlled cameras manage the cursor
+fn manages_cursor(
+    active: &Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    cursor_cams: &CursorCameras,
+) -> bool {
+    let any_active = !active.is_empty();
+    cursor_cams
+        .iter()
+        .any(|is_active| is_controlled(any_active, is_active))
+}
+
+/// Applies the configured startup cursor state, unless none of the cameras
+/// manage the cursor. This runs after the startup stage, so that cameras
+/// spawned at startup are considered.
+fn apply_start_cursor(
+    config: Res<Config>,
+    mut windows: ResMut<Windows>,
+    mut cursor: ResMut<CursorControl>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    cursor_cams: CursorCameras,
+) {
+    if !manages_cursor(&active, &cursor_cams) {
+        return;
+    }
+
+    cursor.set_locked(&mut windows, config.start_locked && !config.editor_mode);
+    if let Some(window) = windows.get_primary_mut() {
+        window.set_cursor_visibility(config.start_cursor_visible || config.editor_mode);
+    }
+}
+
+/// Handles unlocking the cursor when any of the `UnlockTriggers` is pressed
+fn unlock_cursor(
+    config: Res<Config>,
+    keys: Res<Input<KeyCode>>,
+    triggers: Res<UnlockTriggers>,
+    mut windows: ResMut<Windows>,
+    mut cursor: ResMut<CursorControl>,
+    mut key_events: EventReader<KeyboardInput>,
+    mut mouse_events: EventReader<MouseButtonInput>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    cursor_cams: CursorCameras,
+) {
+    let manage = manages_cursor(&active, &cursor_cams) && !config.editor_mode;
+    for kev in key_events.iter() {
+        if let Some(code) = kev.key_code {
+            let triggered = kev.state == ElementState::Pressed
+                && triggers.0.iter().any(|trigger| match *trigger {
+                    UnlockTrigger::Key(binding) => {
+                        is_bound(Some(binding), code, |key| keys.pressed(key))
+                    }
+                    UnlockTrigger::Mouse(_) => false,
+                });
+            if manage && triggered {
+                cursor.unlock(&mut windows);
+            }
+        }
+    }
+    for ev in mouse_events.iter() {
+        if manage
+            && ev.state == ElementState::Pressed
+            && triggers.0.contains(&UnlockTrigger::Mouse(ev.button))
+        {
+            cursor.unlock(&mut windows);
+        }
+    }
+}
+
+/// Handles locking the cursor when the client area is clicked, or while the
+/// look button is held in editor mode, and keeps `CursorLockStatus` up to date
+fn lock_cursor(
+    config: Res<Config>,
+    triggers: Res<UnlockTriggers>,
+    mut windows: ResMut<Windows>,
+    mut cursor: ResMut<CursorControl>,
+    mut status: ResMut<CursorLockStatus>,
+    mut pending_frames: Local<u32>,
+    mut mouse_events: EventReader<MouseButtonInput>,
+    active: Query<(), (With<FpsCam>, With<ActiveFpsCam>)>,
+    cursor_cams: CursorCameras,
+) {
+    let manage = manages_cursor(&active, &cursor_cams);
+    for ev in mouse_events.iter() {
+        if !manage {
+            continue;
+        }
+
+        if config.editor_mode {
+            if ev.button == config.editor_look_button {
+                cursor.set_locked(&mut windows, ev.state == ElementState::Pressed);
+                if let Some(window) = windows.get_primary_mut() {
+                    window.set_cursor_visibility(true);
+                }
+            }
+        } else if ev.state == ElementState::Pressed
+            && !triggers.0.contains(&UnlockTrigger::Mouse(ev.button))
+        {
+            // the lock is requested on every click, even if the cursor is
+            // believed to be locked already, so that a rejected request is
+            // retried by the next click
+            cursor.lock(&mut windows);
+            *status = CursorLockStatus::Pending;
+            *pending_frames = 0;
+        }
+    }
+
+    if *status == CursorLockStatus::Pending {
+        if cursor.is_locked(&windows) {
+            *status = CursorLockStatus::Idle;
+        } else {
+            *pending_frames += 1;
+            if *pending_frames >= LOCK_REQUEST_FRAMES {
+                *status = CursorLockStatus::Failed;
+            }
+        }
+    }
+}
+
+/// Glides the cursor to the center of the window after it is unlocked, if
+/// `Config::recenter_smoothly` is enabled
+fn recenter_cursor(
+    config: Res<Config>,
+    mut windows: ResMut<Windows>,
+    cursor: Res<CursorControl>,
+    mut was_locked: Local<bool>,
+    mut glide: Local<Option<(Vec2, u32)>>,
+) {
+    let locked = cursor.is_locked(&windows);
+    let window = match windows.get_primary_mut() {
+        Some(window) => window,
+        None => return,
+    };
+    let center = Vec2::new(window.width(), window.height()) / 2.0;
+
+    if *was_locked && !locked && config.recenter_smoothly {
+        *glide = Some((window.cursor_position().unwrap_or(center), 0));
+    }
+    *was_locked = locked;
+    if locked {
+        *glide = None;
+    }
+
+    if let Some((start, frame)) = *glide {
+        let frame = frame + 1;
+        let t = frame as f32 / RECENTER_CURSOR_FRAMES as f32;
+        window.set_cursor_position(start.lerp(center, ease(t.min(1.0))));
+        *glide = if frame < RECENTER_CURSOR_FRAMES {
+            Some((start, frame))
+        } else {
+            None
+        };
+    }
+}
+
+/// Spawns a perspective camera with an `FpsCam` as a child of `parent`, for
+/// attaching the camera to a rig. `transform` is relative to the parent, and the
+/// camera moves within the frame of the parent. The yaw and pitch are
+/// initialized from the rotation of `transform`. Returns the camera entity.
+pub fn spawn_fpscam_child(cmd: &mut Commands, parent: Entity, transform: Transform) -> Entity {
+    let (yaw, pitch) = yaw_pitch_from_rotation(transform.rotation);
+
+    let camera = cmd
+        .spawn_bundle(PerspectiveCameraBundle {
+            transform,
+            ..Default::default()
+        })
+        .insert(FpsCam { yaw, pitch })
+        .id();
+    cmd.entity(parent).add_child(camera);
+
+    camera
+}
+
+/// Yaw and pitch the camera spawns with. A `look_at` target on the start
+/// position or straight above or below it has no defined heading, so it falls
+/// back to `Config::start_yaw` and `Config::start_pitch`
+fn start_yaw_pitch(config: &Config, spawn_config: &CameraSpawnConfig) -> (f32, f32) {
+    let basis = up_basis(config, config.start_position);
+    let up = basis * Vec3::Y;
+    let (yaw, pitch) = match spawn_config.look_at.filter(|&target| {
+        let direction = target - config.start_position;
+        direction.cross(up).length_squared() > f32::EPSILON * direction.length_squared()
+    }) {
+        Some(target) => {
+            let looking_at =
+                Transform::from_translation(config.start_position).looking_at(target, up);
+            yaw_pitch_from_rotation(basis.inverse() * looking_at.rotation)
+        }
+        None => (config.start_yaw, config.start_pitch),
+    };
+    (yaw, limit_pitch(pitch, config))
+}
+
+/// Spawns the camera
+fn spawn_camera(mut cmd: Commands, config: Res<Config>, spawn_config: Res<CameraSpawnConfig>) {
+    let basis = up_basis(&config, config.start_position);
+    let (yaw, pitch) = start_yaw_pitch(&config, &spawn_config);
+    let transform = Transform {
+        translation: config.start_position,
+        rotation: basis * look_rotation(yaw, pitch),
+        ..Default::default()
+    };
+
+    if let Some(color) = spawn_config.clear_color {
+        cmd.insert_resource(ClearColor(color));
+    }
+
+    let mut camera = match &spawn_config.projection {
+        SpawnProjection::Perspective(projection) => cmd.spawn_bundle(PerspectiveCameraBundle {
+            transform,
+            perspective_projection: projection.clone(),
+            ..Default::default()
+        }),
+        SpawnProjection::Orthographic(projection) => {
+            let mut bundle = OrthographicCameraBundle::new_3d();
+            bundle.transform = transform;
+            bundle.orthographic_projection = projection.clone();
+            cmd.spawn_bundle(bundle)
+        }
+    };
+    camera.insert(FpsCam { yaw, pitch });
+
+    if let Some(layers) = spawn_config.render_layers {
+        camera.insert(layers);
+    }
+}
+
+fn set_cursor_lock(window: &mut Window, state: bool) {
+    window.set_cursor_lock_mode(state);
+    window.set_cursor_visibility(!state);
+}
+
+/// Controls locking the cursor. Implement this and insert it into the
+/// `CursorControl` resource to customize how the cursor is locked, for example
+/// to integrate with a custom windowing abstraction, or to test without a
+/// window. The implementation is free to ignore the `Windows` it is given.
+pub trait CursorController: Send + Sync + 'static {
+    fn lock(&mut self, windows: &mut Windows);
+    fn unlock(&mut self, windows: &mut Windows);
+    fn is_locked(&self, windows: &Windows) -> bool;
+}
+
+/// The default `CursorController`, which locks and hides the cursor of the
+/// primary window
+#[derive(Default, Debug, Clone, Copy)]
+pub struct WindowCursor;
+
+impl CursorController for WindowCursor {
+    fn lock(&mut self, windows: &mut Windows) {
+        if let Some(window) = windows.get_primary_mut() {
+            set_cursor_lock(window, true);
+        }
+    }
+
+    fn unlock(&mut self, windows: &mut Windows) {
+        if let Some(window) = windows.get_primary_mut() {
+            set_cursor_lock(window, false);
+        }
+    }
+
+    fn is_locked(&self, windows: &Windows) -> bool {
+        windows
+            .get_primary()
+            .map_or(false, |window| window.cursor_locked())
+    }
+}
+
+/// The `CursorController` used by the plugin. Defaults to `WindowCursor`.
+pub struct CursorControl(pub Box<dyn CursorController>);
+
+impl CursorControl {
+    pub fn new(controller: impl CursorController) -> Self {
+        Self(Box::new(controller))
+    }
+
+    pub fn lock(&mut self, windows: &mut Windows) {
+        self.0.lock(windows);
+    }
+
+    pub fn unlock(&mut self, windows: &mut Windows) {
+        self.0.unlock(windows);
+    }
+
+    pub fn set_locked(&mut self, windows: &mut Windows, locked: bool) {
+        if locked {
+            self.lock(windows);
+        } else {
+            self.unlock(windows);
+        }
+    }
+
+    pub fn is_locked(&self, windows: &Windows) -> bool {
+        self.0.is_locked(windows)
+    }
+}
+
+impl Default for CursorControl {
+    fn default() -> Self {
+        Self::new(WindowCursor)
+    }
+}
+
+/// Whether time is paused because the cursor is unlocked, see
+/// `Config::pause_time_on_unlock`
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimePaused(pub bool);
+
+/// A run criteria which only runs systems while the cursor is locked, for
+/// pausing the game while it is unlocked, for example while a menu is open:
+/// `.with_run_criteria(run_if_cursor_locked)`. The `Time` of bevy has no way
+/// to be paused, so this, or checking `TimePaused`, is the way to have
+/// gameplay stop when the cursor is unlocked. Every system which should pause
+/// has to use this itself, and `Time::seconds_since_startup` still advances
+/// while paused.
+pub fn run_if_cursor_locked(windows: Res<Windows>, cursor: Res<CursorControl>) -> ShouldRun {
+    if cursor.is_locked(&windows) {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+/// Spawns a camera and sets up the controls.
+pub struct FpsCamPlugin;
+impl Plugin for FpsCamPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(NoSpawnFpsCamPlugin)
+            .init_resource::<CameraSpawnConfig>()
+            .add_startup_system(spawn_camera);
+    }
+}
+
+/// Spawns a camera set up for free six degrees of freedom navigation, by adding
+/// `FpsCamPlugin` with `Config::spectator` as the configuration. Insert a
+/// `Config` after adding this plugin to customize it further.
+pub struct SpectatorCamPlugin;
+impl Plugin for SpectatorCamPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Config::spectator())
+            .add_plugin(FpsCamPlugin);
+    }
+}
+
+/// Sets up the controls, but does not actually spawn a camera.
+pub struct NoSpawnFpsCamPlugin;
+impl Plugin for NoSpawnFpsCamPlugin {
+    fn build(&self, app: &mut App) {
+        build_controls(app, None);
+    }
+}
+
+/// Sets up the controls like `FpsCamPlugin`, or `NoSpawnFpsCamPlugin` if
+/// created with `no_spawn`, but runs the movement and look of the cameras at a
+/// fixed timestep of `step` seconds instead of once per frame, for
+/// deterministic simulations such as rollback netcode.
+///
+/// Input is still read every frame. Mouse, touch and other look motion is
+/// accumulated until the next step, which then receives all of it, so no
+/// motion is lost on frames without a step and it isn't counted twice on frames
+/// with several. Held keys are sampled at every step. Presses of bindings which
+/// trigger once per press, like `fast_toggle`, `dash` or the movement keys in
+/// `MovementMode::Step`, are likewise remembered until the next step, which
+/// handles each of them once, so a press is neither lost on a frame without a
+/// step nor handled twice on a frame with several.
+pub struct FixedTimestepFpsCamPlugin {
+    pub step: f64,
+    pub spawn: bool,
+}
+
+impl FixedTimestepFpsCamPlugin {
+    /// Spawns a camera, controlled at a fixed timestep of `step` seconds
+    pub fn new(step: f64) -> Self {
+        Self { step, spawn: true }
+    }
+
+    /// Controls cameras at a fixed timestep of `step` seconds without spawning
+    /// one
+    pub fn no_spawn(step: f64) -> Self {
+        Self { step, spawn: false }
+    }
+}
+
+impl Plugin for FixedTimestepFpsCamPlugin {
+    fn build(&self, app: &mut App) {
+        build_controls(app, Some(self.step));
+        if self.spawn {
+            app.init_resource::<CameraSpawnConfig>()
+                .add_startup_system(spawn_camera);
+        }
+    }
+}
+
+/// Sets up the controls, running the cameras once per frame or at
+/// `fixed_step` if set
+fn build_controls(app: &mut App, fixed_step: Option<f64>) {
+    app.init_resource::<Config>()
+        .insert_resource(CameraTime {
+            fixed_step: fixed_step.map(|step| step as f32),
+            ..Default::default()
+        })
+        .init_resource::<CursorControl>()
+        .init_resource::<UnlockTriggers>()
+        .init_resource::<CursorLockStatus>()
+        .init_resource::<TimePaused>()
+        .init_resource::<LookMotion>()
+        .init_resource::<PreviousActiveCamera>()
+        .init_resource::<TouchMovement>()
+        .init_resource::<PendingPresses>()
+        .init_resource::<FastMovement>()
+        .init_resource::<CurrentMovementInput>()
+        .init_resource::<LookAxisLocks>()
+        .init_resource::<MovementProfiles>()
+        .init_resource::<LastInputDevice>()
+        .add_event::<FpsCamMoved>()
+        .add_event::<FpsCamLooked>()
+        .add_event::<BoundsHit>()
+        .add_event::<HighSpeed>()
+        .add_event::<LookInput>()
+        .add_event::<RecoilKick>()
+        .add_event::<RebindAction>()
+        .add_plugin(MotionSourcePlugin::<LookInput>::default())
+        .add_startup_system_to_stage(StartupStage::PostStartup, apply_start_cursor)
+        .add_system(init_camera_state)
+        // in an earlier stage, so that the marker is in place before any
+        // camera is controlled
+        .add_system_to_stage(CoreStage::PreUpdate, ensure_active_camera)
+        .add_system(
+            read_mouse_motion
+                .label(FpsCamSystem::ReadMotion)
+                .before(FpsCamSystem::Control),
+        )
+        .add_system(
+            read_touch
+                .label(FpsCamSystem::ReadMotion)
+                .before(FpsCamSystem::Control),
+        )
+        .add_system(
+            latch_presses
+                .label(FpsCamSystem::LatchPresses)
+                .before(FpsCamSystem::Control),
+        )
+        .add_system(track_input_device)
+        .add_system(lock_on_focus)
+        .add_system(stop_on_focus_loss.before(FpsCamSystem::Control))
+        .add_system(rebind_actions.before(FpsCamSystem::Control))
+        .add_system(lock_cursor)
+        .add_system(unlock_cursor)
+        .add_system(recenter_cursor);
+
+    // everything which advances the cameras is in one set, so that all of it
+    // runs together at every step when using a fixed timestep
+    let mut cameras = SystemSet::new()
+        .with_system(
+            update_camera_time
+                .label(FpsCamSystem::Time)
+                .before(FpsCamSystem::Control),
+        )
+        .with_system(
+            update_sensitivity
+                .after(FpsCamSystem::Time)
+                .before(FpsCamSystem::Control),
+        )
+        .with_system(
+            toggle_look_locks
+                .after(FpsCamSystem::LatchPresses)
+                .before(FpsCamSystem::Control),
+        )
+        .with_system(
+            reset_camera_state
+                .label(FpsCamSystem::Reset)
+                .before(FpsCamSystem::Control),
+        )
+        .with_system(
+            sync_from_transform
+                .after(FpsCamSystem::Reset)
+                .before(FpsCamSystem::Control),
+        )
+        .with_system(
+            blend_camera_switch
+                .after(FpsCamSystem::Reset)
+                .before(FpsCamSystem::Control),
+        )
+        .with_system(
+            apply_yaw_pitch
+                .after(FpsCamSystem::Reset)
+                .before(FpsCamSystem::Control),
+        )
+        .with_system(
+            auto_level
+                .after(FpsCamSystem::Reset)
+                .before(FpsCamSystem::Control),
+        )
+        .with_system(camera_move.label(FpsCamSystem::Control))
+        .with_system(camera_gamepad.label(FpsCamSystem::Control))
+        .with_system(camera_look.label(FpsCamSystem::Control))
+        .with_system(camera_keyboard_look.label(FpsCamSystem::Control))
+        .with_system(camera_recenter_pitch.label(FpsCamSystem::Control))
+        .with_system(camera_move_to.label(FpsCamSystem::Control))
+        .with_system(camera_orbit.label(FpsCamSystem::Control))
+        .with_system(camera_zoom.label(FpsCamSystem::Control))
+        .with_system(camera_crouch.label(FpsCamSystem::Control))
+        .with_system(camera_roll.label(FpsCamSystem::Control))
+        .with_system(camera_recoil.label(FpsCamSystem::Control))
+        .with_system(camera_dash.label(FpsCamSystem::Control))
+        .with_system(camera_playback.label(FpsCamSystem::Control))
+        .with_system(
+            clamp_to_ground
+                .after(FpsCamSystem::Control)
+                .before(FpsCamSystem::ApplyOffset),
+        )
+        .with_system(
+            record_path
+                .after(FpsCamSystem::Control)
+                .before(FpsCamSystem::ApplyOffset),
+        )
+        .with_system(
+            apply_view_offset
+                .label(FpsCamSystem::ApplyOffset)
+                .after(FpsCamSystem::Control),
+        )
+        .with_system(consume_presses.after(FpsCamSystem::Control));
+    if let Some(step) = fixed_step {
+        cameras = cameras.with_run_criteria(FixedTimestep::step(step));
+    }
+    app.add_system_set(cameras);
+}
+
+/// Feeds events of type `M` into the camera look, in addition to `MouseMotion`
+/// unless `Config::use_mouse_motion` is disabled. The event type must be
+/// registered separately.
+pub struct MotionSourcePlugin<M>(PhantomData<M>);
+
+impl<M> Default for MotionSourcePlugin<M> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<M: MotionSource> Plugin for MotionSourcePlugin<M> {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            read_motion::<M>
+                .label(FpsCamSystem::ReadMotion)
+                .before(FpsCamSystem::Control),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::event::Events;
+
+    fn assert_near(actual: Vec3, expected: Vec3) {
+        assert!(
+            actual.abs_diff_eq(expected, 1e-5),
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    /// A `CursorController` which only remembers whether it is locked, so
+    /// the cursor handling can be tested without a window
+    #[derive(Default)]
+    struct MockCursor {
+        locked: bool,
+    }
+
+    impl CursorController for MockCursor {
+        fn lock(&mut self, _: &mut Windows) {
+            self.locked = true;
+        }
+
+        fn unlock(&mut self, _: &mut Windows) {
+            self.locked = false;
+        }
+
+        fn is_locked(&self, _: &Windows) -> bool {
+            self.locked
+        }
+    }
+
+    fn cursor_locked(app: &App) -> bool {
+        let cursor = app.world.get_resource::<CursorControl>().unwrap();
+        cursor.is_locked(&Windows::default())
+    }
+
+    fn set_cursor_locked(app: &mut App, locked: bool) {
+        let mut cursor = app.world.get_resource_mut::<CursorControl>().unwrap();
+        cursor.set_locked(&mut Windows::default(), locked);
+    }
+
+    /// Presses `key`, both in `Input<KeyCode>` and as a `KeyboardInput` event
+    /// if the app has those events
+    fn press_key(app: &mut App, key: KeyCode) {
+        app.world
+            .get_resource_mut::<Input<KeyCode>>()
+            .unwrap()
+            .press(key);
+        if let Some(mut events) = app.world.get_resource_mut::<Events<KeyboardInput>>() {
+            events.send(KeyboardInput {
+                scan_code: 0,
+                key_code: Some(key),
+                state: ElementState::Pressed,
+            });
+        }
+    }
+
+    /// An app running only `unlock_cursor`, with a single camera and the
+    /// cursor locked
+    fn unlock_app(config: Config) -> App {
+        let mut app = App::new();
+        app.insert_resource(config)
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<UnlockTriggers>()
+            .init_resource::<Windows>()
+            .insert_resource(CursorControl::new(MockCursor { locked: true }))
+            .add_event::<KeyboardInput>()
+            .add_event::<MouseButtonInput>()
+            .add_system(unlock_cursor);
+        app.world.spawn().insert(FpsCam::default());
+        app
+    }
+
+    /// The timestep of a frame in the tests running systems
+    const FRAME: f32 = 0.1;
+
+    fn frame_time() -> CameraTime {
+        CameraTime {
+            delta: FRAME,
+            smoothing_delta: FRAME,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unlocking_pauses_time_if_enabled() {
+        for pause in [false, true] {
+            let mut app = App::new();
+            app.insert_resource(Config {
+                pause_time_on_unlock: pause,
+                ..Default::default()
+            })
+            .init_resource::<Time>()
+            .init_resource::<Windows>()
+            .insert_resource(CursorControl::new(MockCursor { locked: true }))
+            .insert_resource(CameraTime {
+                fixed_step: Some(FRAME),
+                ..Default::default()
+            })
+            .init_resource::<TimePaused>()
+            .add_system(update_camera_time);
+            let delta = |app: &App| app.world.get_resource::<CameraTime>().unwrap().delta;
+            let paused = |app: &App| app.world.get_resource::<TimePaused>().unwrap().0;
+
+            app.update();
+            assert_eq!(delta(&app), FRAME);
+            assert!(!paused(&app));
+
+            set_cursor_locked(&mut app, false);
+            app.update();
+            assert_eq!(delta(&app), if pause { 0.0 } else { FRAME });
+            assert_eq!(paused(&app), pause);
+
+            set_cursor_locked(&mut app, true);
+            app.update();
+            assert_eq!(delta(&app), FRAME);
+            assert!(!paused(&app));
+        }
+    }
+
+    #[test]
+    fn recentering_stays_within_the_pitch_limits() {
+        let config = Config {
+            pitch_min: 0.2,
+            pitch_max: 1.0,
+            key_bindings: KeyBindings {
+                recenter_pitch: Some(KeyCode::C.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut app = App::new();
+        app.insert_resource(config)
+            .init_resource::<Input<KeyCode>>()
+            .insert_resource(frame_time())
+            .init_resource::<Windows>()
+            .insert_resource(CursorControl::new(MockCursor { locked: true }))
+            .insert_resource(PendingPresses(vec![KeyCode::C]))
+            .add_event::<FpsCamLooked>()
+            .add_system(camera_recenter_pitch);
+        let camera = app
+            .world
+            .spawn()
+            .insert(Transform::default())
+            .insert(FpsCam {
+                yaw: 0.0,
+                pitch: 0.8,
+            })
+            .insert(LookState::default())
+            .id();
+        let pitch = |app: &App| app.world.get::<FpsCam>(camera).unwrap().pitch;
+
+        app.update();
+        app.world
+            .get_resource_mut::<PendingPresses>()
+            .unwrap()
+            .0
+            .clear();
+        for _ in 0..50 {
+            assert!(pitch(&app) >= 0.2, "pitch {}", pitch(&app));
+            app.update();
+        }
+        assert!((pitch(&app) - 0.2).abs() < 1e-6, "pitch {}", pitch(&app));
+    }
+
+    #[test]
+    fn move_to_ends_within_the_look_limits() {
+        let config = Config {
+            pitch_min: -0.5,
+            pitch_max: 0.5,
+            yaw_limits: Some((-1.0, 1.0)),
+            ..Default::default()
+        };
+        let mut app = App::new();
+        app.insert_resource(config)
+            .insert_resource(frame_time())
+            .add_system(camera_move_to);
+        let target = Transform {
+            translation: Vec3::X,
+            rotation: look_rotation(2.0, 1.2),
+            ..Default::default()
+        };
+        let camera = app
+            .world
+            .spawn()
+            .insert(Transform::default())
+            .insert(FpsCam::default())
+            .insert(MoveTo::new(target, 0.5))
+            .id();
+
+        for _ in 0..10 {
+            app.update();
+        }
+        assert!(app.world.get::<MoveTo>(camera).is_none());
+        let fpscam = *app.world.get::<FpsCam>(camera).unwrap();
+        assert_eq!((fpscam.yaw, fpscam.pitch), (1.0, 0.5));
+        let rotation = app.world.get::<Transform>(camera).unwrap().rotation;
+        assert!(rotation.angle_between(look_rotation(1.0, 0.5)) < 1e-5);
+    }
+
+    #[test]
+    fn orbit_stays_within_the_look_limits() {
+        let config = Config {
+            pitch_min: -0.5,
+            pitch_max: 0.5,
+            ..Default::default()
+        };
+        let mut app = App::new();
+        app.insert_resource(config)
+            .insert_resource(frame_time())
+            .add_system(camera_orbit);
+        let camera = app
+            .world
+            .spawn()
+            .insert(Transform::from_translation(Vec3::Z))
+            .insert(FpsCam::default())
+            .insert(Orbit {
+                center: Vec3::ZERO,
+                radius: 1.0,
+                speed: 1.0,
+                height: 10.0,
+            })
+            .id();
+
+        app.update();
+        let fpscam = *app.world.get::<FpsCam>(camera).unwrap();
+        assert_eq!(fpscam.pitch, -0.5);
+        let rotation = app.world.get::<Transform>(camera).unwrap().rotation;
+        assert!(rotation.angle_between(look_rotation(fpscam.yaw, -0.5)) < 1e-5);
+    }
+
+    #[test]
+    fn playback_stays_within_the_look_limits() {
+        let config = Config {
+            pitch_min: -0.5,
+            pitch_max: 0.5,
+            ..Default::default()
+        };
+        let mut app = App::new();
+        app.insert_resource(config)
+            .insert_resource(frame_time())
+            .add_system(camera_playback);
+        let path = CameraPath {
+            keyframes: vec![
+                (0.0, Transform::default()),
+                (0.2, Transform::from_rotation(look_rotation(0.3, 1.2))),
+            ],
+        };
+        let camera = app
+            .world
+            .spawn()
+            .insert(Transform::default())
+            .insert(FpsCam::default())
+            .insert(PathPlayback::new(path))
+            .id();
+
+        for _ in 0..3 {
+            app.update();
+            let fpscam = *app.world.get::<FpsCam>(camera).unwrap();
+            assert!(fpscam.pitch <= 0.5, "pitch {}", fpscam.pitch);
+            let rotation = app.world.get::<Transform>(camera).unwrap().rotation;
+            let composed = look_rotation(fpscam.yaw, fpscam.pitch);
+            assert!(rotation.angle_between(composed) < 1e-4);
+        }
+        assert!(app.world.get::<PathPlayback>(camera).is_none());
+        let fpscam = *app.world.get::<FpsCam>(camera).unwrap();
+        assert!((fpscam.yaw - 0.3).abs() < 1e-4, "yaw {}", fpscam.yaw);
+        assert_eq!(fpscam.pitch, 0.5);
+    }
+
+    /// An app running only `camera_look`, with a single camera at the origin
+    /// and the cursor locked
+    fn look_app(config: Config) -> (App, Entity) {
+        let mut app = App::new();
+        app.insert_resource(config)
+            .init_resource::<Input<KeyCode>>()
+            .insert_resource(frame_time())
+            .init_resource::<Windows>()
+            .insert_resource(CursorControl::new(MockCursor { locked: true }))
+            .init_resource::<LookAxisLocks>()
+            .init_resource::<LookMotion>()
+            .add_event::<FpsCamLooked>()
+            .add_system(camera_look);
+        let camera = app
+            .world
+            .spawn()
+            .insert(Transform::default())
+            .insert(FpsCam::default())
+            .insert(LookState::default())
+            .id();
+        (app, camera)
+    }
+
+    /// An app running only `camera_move`, with a single camera at the origin
+    /// and the cursor locked
+    fn move_app(config: Config) -> (App, Entity) {
+        let mut app = App::new();
+        app.insert_resource(config)
+            .init_resource::<Input<KeyCode>>()
+            .insert_resource(frame_time())
+            .init_resource::<Windows>()
+            .insert_resource(CursorControl::new(MockCursor { locked: true }))
+            .init_resource::<PendingPresses>()
+            .init_resource::<FastMovement>()
+            .init_resource::<TouchMovement>()
+            .init_resource::<MovementProfiles>()
+            .init_resource::<CurrentMovementInput>()
+            .add_event::<FpsCamMoved>()
+            .add_event::<BoundsHit>()
+            .add_event::<HighSpeed>()
+            .add_system(camera_move);
+        let camera = app
+            .world
+            .spawn()
+            .insert(Transform::default())
+            .insert(FpsCam::default())
+            .insert(MovementState::default())
+            .id();
+        (app, camera)
+    }
+
+    /// Runs a frame, returning how far `camera` moved in it
+    fn step(app: &mut App, camera: Entity) -> Vec3 {
+        let before = app.world.get::<Transform>(camera).unwrap().translation;
+        app.update();
+        // what `Input` does at the end of every frame in a full app
+        app.world
+            .get_resource_mut::<Input<KeyCode>>()
+            .unwrap()
+            .clear();
+        app.world.get::<Transform>(camera).unwrap().translation - before
+    }
+
+    /// Runs a frame in which the mouse moved by `delta`
+    fn look(app: &mut App, delta: Vec2) {
+        app.world.get_resource_mut::<LookMotion>().unwrap().0 += delta;
+        app.update();
+    }
+
+    fn yaw_pitch(app: &App, camera: Entity) -> (f32, f32) {
+        let fpscam = app.world.get::<FpsCam>(camera).unwrap();
+        (fpscam.yaw, fpscam.pitch)
+    }
+
+    /// How far `apply_movement` moves a camera at the origin, looking down -Z,
+    /// in one second
+    fn moved_by(keys: &[KeyCode], config: &Config) -> Vec3 {
+        let mut transform = Transform::default();
+        apply_movement(&mut transform, keys, config, 1.0);
+        transform.translation
+    }
+
+    #[test]
+    fn apply_movement_moves_in_each_direction() {
+        let config = Config::default();
+
+        assert_near(moved_by(&[KeyCode::W], &config), -Vec3::Z);
+        assert_near(moved_by(&[KeyCode::S], &config), Vec3::Z);
+        assert_near(moved_by(&[KeyCode::A], &config), -Vec3::X);
+        assert_near(moved_by(&[KeyCode::D], &config), Vec3::X);
+        assert_near(moved_by(&[KeyCode::Space], &config), Vec3::Y);
+        assert_near(moved_by(&[KeyCode::LControl], &config), -Vec3::Y);
+        assert_near(moved_by(&[], &config), Vec3::ZERO);
+    }
+
+    #[test]
+    fn apply_movement_normalizes_diagonals() {
+        let config = Config::default();
+
+        let diagonal = moved_by(&[KeyCode::W, KeyCode::D], &config);
+        assert_near(diagonal, Vec3::new(1.0, 0.0, -1.0).normalize());
+
+        let all_three = moved_by(&[KeyCode::W, KeyCode::D, KeyCode::Space], &config);
+        assert_near(all_three, Vec3::new(1.0, 1.0, -1.0).normalize());
+
+        // opposite keys cancel out rather than normalizing zero
+        assert_near(moved_by(&[KeyCode::W, KeyCode::S], &config), Vec3::ZERO);
+    }
+
+    #[test]
+    fn apply_movement_scales_with_speed_and_timestep() {
+        let config = Config {
+            movespeed: 3.0,
+            ..Default::default()
+        };
+
+        let mut transform = Transform::default();
+        apply_movement(&mut transform, &[KeyCode::W], &config, 0.5);
+        assert_near(transform.translation, Vec3::new(0.0, 0.0, -1.5));
+    }
+
+    #[test]
+    fn unlock_binding_unlocks_the_cursor() {
+        let mut app = unlock_app(Config::default());
+        press_key(&mut app, KeyCode::Escape);
+        app.update();
+        assert!(!cursor_locked(&app));
+    }
+
+    #[test]
+    fn any_unlock_trigger_unlocks_the_cursor() {
+        let mut app = unlock_app(Config::default());
+        app.insert_resource(UnlockTriggers(vec![
+            UnlockTrigger::Key(KeyCode::Tab.into()),
+            UnlockTrigger::Mouse(MouseButton::Right),
+        ]));
+
+        // Escape is no longer a trigger once the list is replaced
+        press_key(&mut app, KeyCode::Escape);
+        app.update();
+        assert!(cursor_locked(&app));
+
+        press_key(&mut app, KeyCode::Tab);
+        app.update();
+        assert!(!cursor_locked(&app));
+
+        set_cursor_locked(&mut app, true);
+        app.world
+            .get_resource_mut::<Events<MouseButtonInput>>()
+            .unwrap()
+            .send(MouseButtonInput {
+                button: MouseButton::Right,
+                state: ElementState::Pressed,
+            });
+        app.update();
+        assert!(!cursor_locked(&app));
+    }
+
+    #[test]
+    fn unbound_unlock_leaves_the_cursor_locked() {
+        let mut app = unlock_app(Config::default());
+        app.insert_resource(UnlockTriggers(Vec::new()));
+
+        press_key(&mut app, KeyCode::Escape);
+        // keys without a key code must not match the missing binding either
+        app.world
+            .get_resource_mut::<Events<KeyboardInput>>()
+            .unwrap()
+            .send(KeyboardInput {
+                scan_code: 0,
+                key_code: None,
+                state: ElementState::Pressed,
+            });
+        app.update();
+        assert!(cursor_locked(&app));
+    }
+
+    /// The movement actions along with the keys they are bound to by default
+    const MOVEMENT_ACTIONS: [(CameraAction, KeyCode); 6] = [
+        (CameraAction::Forward, KeyCode::W),
+        (CameraAction::Back, KeyCode::S),
+        (CameraAction::Left, KeyCode::A),
+        (CameraAction::Right, KeyCode::D),
+        (CameraAction::Up, KeyCode::Space),
+        (CameraAction::Down, KeyCode::LControl),
+    ];
+
+    #[test]
+    fn unbound_movement_actions_do_nothing() {
+        let transform = Transform::default();
+        for (action, key) in MOVEMENT_ACTIONS {
+            let mut config = Config::default();
+            *config.key_bindings.binding_mut(action) = None;
+
+            assert_near(moved_by(&[key], &config), Vec3::ZERO);
+            let input = movement_input(&transform, &[key], &config, |pressed| pressed == key);
+            assert_near(input, Vec3::ZERO);
+        }
+    }
+
+    #[test]
+    fn disabled_movement_actions_do_nothing() {
+        let transform = Transform::default();
+        for (action, key) in MOVEMENT_ACTIONS {
+            let mut config = Config::default();
+            if let Some(binding) = config.key_bindings.binding_mut(action) {
+                binding.enabled = false;
+            }
+
+            assert_near(moved_by(&[key], &config), Vec3::ZERO);
+            let input = movement_input(&transform, &[key], &config, |pressed| pressed == key);
+            assert_near(input, Vec3::ZERO);
+        }
+    }
+
+    #[test]
+    fn unbound_actions_match_no_key() {
+        let mut config = Config::default();
+        for (action, _) in MOVEMENT_ACTIONS {
+            *config.key_bindings.binding_mut(action) = None;
+        }
+
+        // a binding of `None` must never turn into a match for every key
+        let keys = [
+            KeyCode::W,
+            KeyCode::A,
+            KeyCode::Q,
+            KeyCode::Escape,
+            KeyCode::Space,
+            KeyCode::LShift,
+        ];
+        assert_near(moved_by(&keys, &config), Vec3::ZERO);
+
+        // the remaining bindings keep working
+        config.key_bindings.right = Some(KeyCode::D.into());
+        assert_near(moved_by(&[KeyCode::W, KeyCode::D], &config), Vec3::X);
+    }
+
+    #[test]
+    fn sub_epsilon_motion_does_not_rotate() {
+        let (mut app, camera) = look_app(Config {
+            look_smoothing: 0.1,
+            ..Default::default()
+        });
+
+        for _ in 0..10 {
+            look(&mut app, Vec2::new(0.001, 0.001));
+        }
+        assert_eq!(yaw_pitch(&app, camera), (0.0, 0.0));
+        assert_eq!(
+            app.world.get::<Transform>(camera).unwrap().rotation,
+            Quat::IDENTITY
+        );
+    }
+
+    #[test]
+    fn smoothed_motion_comes_to_a_full_stop() {
+        let (mut app, camera) = look_app(Config {
+            look_smoothing: 0.1,
+            ..Default::default()
+        });
+
+        look(&mut app, Vec2::new(100.0, 0.0));
+        assert!(yaw_pitch(&app, camera).0 != 0.0);
+
+        for _ in 0..50 {
+            app.update();
+        }
+        let settled = yaw_pitch(&app, camera);
+        for _ in 0..50 {
+            app.update();
+        }
+        assert_eq!(yaw_pitch(&app, camera), settled);
+    }
+
+    #[test]
+    fn yaw_pitch_round_trips_through_rotation() {
+        use std::f32::consts::FRAC_PI_2;
+
+        let pitches = [
+            0.0,
+            0.7,
+            -0.7,
+            FRAC_PI_2 - 1e-3,
+            -(FRAC_PI_2 - 1e-3),
+            FRAC_PI_2,
+            -FRAC_PI_2,
+        ];
+        for yaw in [-2.0, 0.0, 0.5, 3.0] {
+            for pitch in pitches {
+                let (y, p) = yaw_pitch_from_rotation(look_rotation(yaw, pitch));
+                // the yaw is still recovered from the right vector when the
+                // forward vector is vertical
+                assert!((y - yaw).abs() < 1e-4, "yaw {} != {}", y, yaw);
+                // asin is ill-conditioned close to ±1
+                assert!((p - pitch).abs() < 1e-3, "pitch {} != {}", p, pitch);
+            }
+        }
+    }
+
+    #[test]
+    fn yaw_pitch_ignore_roll() {
+        for yaw in [-2.0, 0.0, 0.5, 3.0] {
+            for pitch in [0.0, 0.7, -0.7, 1.5] {
+                for roll in [0.5, -0.5, 2.0] {
+                    let rotation = look_rotation(yaw, pitch) * Quat::from_rotation_z(roll);
+                    let (y, p) = yaw_pitch_from_rotation(rotation);
+                    assert!((y - yaw).abs() < 1e-4, "yaw {} != {}", y, yaw);
+                    assert!((p - pitch).abs() < 1e-3, "pitch {} != {}", p, pitch);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn yaw_pitch_of_known_rotations() {
+        use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+        let (yaw, pitch) = yaw_pitch_from_rotation(Quat::IDENTITY);
+        assert!(yaw.abs() < 1e-6 && pitch.abs() < 1e-6);
+
+        // looking straight down along -Y, with the top of the view towards -Z
+        let down = Transform::default().looking_at(-Vec3::Y, -Vec3::Z).rotation;
+        let (yaw, pitch) = yaw_pitch_from_rotation(down);
+        assert!(yaw.abs() < 1e-4, "yaw {}", yaw);
+        assert!((pitch + FRAC_PI_2).abs() < 1e-3, "pitch {}", pitch);
+
+        // looking at the origin from (1, 1, 1), like the default camera
+        let rotation = Transform::from_translation(Vec3::ONE)
+            .looking_at(Vec3::ZERO, Vec3::Y)
+            .rotation;
+        let (yaw, pitch) = yaw_pitch_from_rotation(rotation);
+        assert!((yaw - FRAC_PI_4).abs() < 1e-4, "yaw {}", yaw);
+        assert!(
+            (pitch - Config::default().start_pitch).abs() < 1e-4,
+            "pitch {}",
+            pitch
+        );
+    }
+
+    #[test]
+    fn look_at_without_heading_uses_start_yaw_and_pitch() {
+        let config = Config {
+            start_yaw: 0.5,
+            start_pitch: -0.2,
+            ..Default::default()
+        };
+        let start = config.start_position;
+        for target in [start, start + Vec3::Y * 3.0, start - Vec3::Y] {
+            let spawn_config = CameraSpawnConfig {
+                look_at: Some(target),
+                ..Default::default()
+            };
+            let (yaw, pitch) = start_yaw_pitch(&config, &spawn_config);
+            assert_eq!((yaw, pitch), (config.start_yaw, config.start_pitch));
+        }
+
+        // a target slightly off the vertical still gets looked at
+        let spawn_config = CameraSpawnConfig {
+            look_at: Some(start + Vec3::new(0.0, 3.0, -0.1)),
+            ..Default::default()
+        };
+        let (yaw, pitch) = start_yaw_pitch(&config, &spawn_config);
+        assert!(yaw.abs() < 1e-4, "yaw {}", yaw);
+        assert!(pitch > 1.0, "pitch {}", pitch);
+    }
+
+    /// An app running `apply_view_offset`, with the offset removed again at
+    /// the start of every frame, and two shaking cameras at the origin
+    fn shake_app(config: Config) -> (App, [Entity; 2]) {
+        let mut app = App::new();
+        app.insert_resource(config)
+            .insert_resource(frame_time())
+            .add_system_to_stage(CoreStage::PreUpdate, reset_camera_state)
+            .add_system(apply_view_offset);
+        let mut spawn = || {
+            app.world
+                .spawn()
+                .insert(Transform::default())
+                .insert(FpsCam::default())
+                .insert(ViewOffset::default())
+                .insert(LookState::default())
+                .insert(MovementState::default())
+                .insert(CameraShake { trauma: 1.0 })
+                .id()
+        };
+        let cameras = [spawn(), spawn()];
+        (app, cameras)
+    }
+
+    /// The rendered transform of `camera` over `frames` frames
+    fn shake_frames(app: &mut App, camera: Entity, frames: usize) -> Vec<Transform> {
+        (0..frames)
+            .map(|_| {
+                app.update();
+                *app.world.get::<Transform>(camera).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shake_with_the_same_seed_is_identical() {
+        let config = Config {
+            effect_seed: 7,
+            ..Default::default()
+        };
+        let (mut app, [a, b]) = shake_app(config);
+
+        let mut shook = false;
+        for _ in 0..10 {
+            app.update();
+            let ta = *app.world.get::<Transform>(a).unwrap();
+            let tb = *app.world.get::<Transform>(b).unwrap();
+            assert_eq!(ta, tb);
+            shook |= ta != Transform::default();
+        }
+        assert!(shook);
+
+        // a separate run with the same seed reproduces the same motion
+        let (mut first, [camera, _]) = shake_app(config);
+        let (mut second, [other, _]) = shake_app(config);
+        assert_eq!(
+            shake_frames(&mut first, camera, 10),
+            shake_frames(&mut second, other, 10)
+        );
+    }
+
+    #[test]
+    fn shake_with_different_seeds_differs() {
+        let (mut first, [a, _]) = shake_app(Config {
+            effect_seed: 1,
+            ..Default::default()
+        });
+        let (mut second, [b, _]) = shake_app(Config {
+            effect_seed: 2,
+            ..Default::default()
+        });
+        assert_ne!(
+            shake_frames(&mut first, a, 10),
+            shake_frames(&mut second, b, 10)
+        );
+    }
+
+    #[test]
+    fn noise_is_deterministic_and_bounded() {
+        for i in 0..100 {
+            let t = i as f32 * 0.37;
+            assert_eq!(noise(3, 0, t), noise(3, 0, t));
+            assert!((-1.0..=1.0).contains(&noise(3, 0, t)));
+        }
+    }
+
+    #[test]
+    fn held_keys_resume_moving_after_relocking() {
+        let (mut app, camera) = move_app(Config::default());
+
+        press_key(&mut app, KeyCode::W);
+        assert_near(step(&mut app, camera), -Vec3::Z * FRAME);
+
+        set_cursor_locked(&mut app, false);
+        assert_near(step(&mut app, camera), Vec3::ZERO);
+        assert_near(step(&mut app, camera), Vec3::ZERO);
+
+        // W is still held, but no longer just pressed
+        set_cursor_locked(&mut app, true);
+        assert_near(step(&mut app, camera), -Vec3::Z * FRAME);
+        assert_near(step(&mut app, camera), -Vec3::Z * FRAME);
+    }
+
+    /// How far the camera turns from 100 counts of mouse motion while zoomed
+    /// by `zoomed`
+    fn zoomed_turn(config: Config, zoomed: Zoomed) -> f32 {
+        let (mut app, camera) = look_app(config);
+        app.world.entity_mut(camera).insert(zoomed);
+        look(&mut app, Vec2::new(100.0, 0.0));
+        -yaw_pitch(&app, camera).0
+    }
+
+    #[test]
+    fn zoom_sensitivity_follows_the_zoom_progress() {
+        let config = Config {
+            sensitivity: 0.001,
+            zoom_sensitivity: Some(0.002),
+            ..Default::default()
+        };
+
+        for (progress, sensitivity) in [(0.0, 0.001), (0.5, 0.0015), (1.0, 0.002)] {
+            let zoomed = Zoomed {
+                base: 1.0,
+                progress,
+                ratio: 1.0,
+                ads: false,
+            };
+            let turned = zoomed_turn(config, zoomed);
+            assert!(
+                (turned - 100.0 * sensitivity).abs() < 1e-5,
+                "turned {} at progress {}",
+                turned,
+                progress
+            );
+        }
+    }
+
+    #[test]
+    fn sensitivity_scales_with_the_current_zoom_ratio() {
+        let config = Config {
+            sensitivity: 0.001,
+            zoom_scales_sensitivity: true,
+            ..Default::default()
+        };
+
+        for ratio in [1.0, 0.75, 0.5] {
+            let zoomed = Zoomed {
+                base: 1.0,
+                progress: 0.5,
+                ratio,
+                ads: false,
+            };
+            let turned = zoomed_turn(config, zoomed);
+            assert!((turned - 0.1 * ratio).abs() < 1e-5, "turned {}", turned);
+        }
+    }
+
+    #[test]
+    fn precision_slows_movement_and_wins_over_sprint() {
+        let config = Config {
+            key_bindings: KeyBindings {
+                precision: Some(KeyCode::LAlt.into()),
+                sprint: Some(KeyCode::LShift.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let precise = moved_by(&[KeyCode::W, KeyCode::LAlt], &config);
+        assert_near(precise, -Vec3::Z * config.precision_multiplier);
+
+        let sprinting = moved_by(&[KeyCode::W, KeyCode::LShift], &config);
+        assert_near(sprinting, -Vec3::Z * config.sprint_multiplier);
+
+        let both = moved_by(&[KeyCode::W, KeyCode::LAlt, KeyCode::LShift], &config);
+        assert_near(both, precise);
+    }
+
+    #[test]
+    fn walking_across_the_bottom_of_a_planet_keeps_the_heading() {
+        let config = Config {
+            gravity_source: Some(Vec3::ZERO),
+            ..Default::default()
+        };
+        // walking along the great circle in the XY plane, through the point
+        // opposite +Y where the up basis changes quickly
+        let position = |angle: f32| Vec3::new(angle.sin(), -angle.cos(), 0.0) * 10.0;
+        let heading = |angle: f32| Vec3::new(angle.cos(), angle.sin(), 0.0);
+
+        let start = position(-0.3);
+        let mut transform =
+            Transform::from_translation(start).looking_at(start + heading(-0.3), start.normalize());
+        let mut fpscam = FpsCam::default();
+        for step in -29..=30 {
+            let angle = step as f32 * 0.01;
+            let from = transform.translation;
+            transform.translation = position(angle);
+            transport_view(&mut transform, &mut fpscam, &config, from);
+
+            let forward = transform.rotation * -Vec3::Z;
+            assert!(
+                forward.abs_diff_eq(heading(angle), 1e-3),
+                "{:?} != {:?} at {}",
+                forward,
+                heading(angle),
+                angle
+            );
+            // the yaw and pitch describe the same view
+            let basis = up_basis(&config, transform.translation);
+            let composed = basis * look_rotation(fpscam.yaw, fpscam.pitch);
+            assert!(
+                composed.abs_diff_eq(transform.rotation, 1e-5),
+                "{:?} != {:?}",
+                composed,
+                transform.rotation
+            );
+        }
+    }
+
+    #[test]
+    fn set_pose_matches_the_rotation_the_plugin_composes() {
+        let config = Config {
+            gravity_source: Some(Vec3::new(0.0, -10.0, 0.0)),
+            pitch_min: -0.5,
+            pitch_max: 0.5,
+            ..Default::default()
+        };
+
+        let mut transform = Transform::default();
+        let mut fpscam = FpsCam::default();
+        fpscam.set_pose(&mut transform, Vec3::new(10.0, 0.0, 0.0), 0.3, 1.2, &config);
+        assert_eq!(fpscam.pitch, 0.5);
+
+        // turning by nothing recomposes the same rotation, so it doesn't snap
+        let rotation = transform.rotation;
+        turn(&mut transform, &mut fpscam, &config, 0.0, 0.0);
+        assert!(
+            transform.rotation.angle_between(rotation) < 1e-5,
+            "{:?} != {:?}",
+            transform.rotation,
+            rotation
+        );
+    }
+
+    fn projection() -> PerspectiveProjection {
+        PerspectiveProjection {
+            fov: std::f32::consts::FRAC_PI_2,
+            aspect_ratio: 2.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn screen_center_hits_the_plane_in_front_of_the_camera() {
+        let window = Vec2::new(800.0, 400.0);
+        let center = window / 2.0;
+
+        let above = Transform::from_xyz(0.0, 10.0, 0.0).looking_at(Vec3::ZERO, -Vec3::Z);
+        let hit = screen_to_plane(&above, &projection(), window, center, 0.0);
+        assert_near(hit.unwrap(), Vec3::ZERO);
+
+        let pitched = Transform::from_xyz(0.0, 5.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y);
+        let (_, direction) = screen_ray(&pitched, &projection(), window, center);
+        assert_near(direction, pitched.forward());
+        let hit = screen_to_plane(&pitched, &projection(), window, center, 2.0);
+        assert_near(hit.unwrap(), Vec3::new(0.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn screen_to_plane_misses_planes_behind_or_along_the_ray() {
+        let window = Vec2::new(800.0, 400.0);
+        let center = window / 2.0;
+
+        let level = Transform::from_xyz(0.0, 1.0, 0.0);
+        assert_eq!(
+            screen_to_plane(&level, &projection(), window, center, 0.0),
+            None
+        );
+
+        let looking_up =
+            Transform::from_xyz(0.0, 1.0, 0.0).looking_at(Vec3::new(0.0, 2.0, -1.0), Vec3::Y);
+        assert_eq!(
+            screen_to_plane(&looking_up, &projection(), window, center, 0.0),
+            None
+        );
+    }
+
+    #[test]
+    fn rapid_zoom_toggling_keeps_the_fov_within_bounds() {
+        let config = Config {
+            key_bindings: KeyBindings {
+                zoom: Some(KeyCode::Z.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let base = PerspectiveProjection::default().fov;
+
+        let mut app = App::new();
+        app.insert_resource(config)
+            .init_resource::<Input<KeyCode>>()
+            .insert_resource(frame_time())
+            .init_resource::<Windows>()
+            .insert_resource(CursorControl::new(MockCursor { locked: true }))
+            .add_system(camera_zoom);
+        let camera = app
+            .world
+            .spawn()
+            .insert(PerspectiveProjection::default())
+            .insert(FpsCam::default())
+            .id();
+
+        let fov = |app: &App| app.world.get::<PerspectiveProjection>(camera).unwrap().fov;
+        for frame in 0..40 {
+            {
+                let mut keys = app.world.get_resource_mut::<Input<KeyCode>>().unwrap();
+                // pressing and releasing in bursts of one to three frames
+                if frame % 3 == 0 || frame % 5 == 0 {
+                    keys.press(KeyCode::Z);
+                } else {
+                    keys.release(KeyCode::Z);
+                }
+            }
+            app.update();
+
+            let fov = fov(&app);
+            assert!(
+                (config.zoom_fov - 1e-5..=base + 1e-5).contains(&fov),
+                "fov {} outside of [{}, {}] in frame {}",
+                fov,
+                config.zoom_fov,
+                base,
+                frame
+            );
+        }
+
+        app.world
+            .get_resource_mut::<Input<KeyCode>>()
+            .unwrap()
+            .release(KeyCode::Z);
+        for _ in 0..10 {
+            app.update();
+        }
+        assert_eq!(fov(&app), base);
+        assert!(app.world.get::<Zoomed>(camera).is_none());
+    }
+
+    #[test]
+    fn zoom_is_ignored_while_unlocked_or_driven() {
+        let config = Config {
+            key_bindings: KeyBindings {
+                zoom: Some(KeyCode::Z.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut app = App::new();
+        app.insert_resource(config)
+            .init_resource::<Input<KeyCode>>()
+            .insert_resource(frame_time())
+            .init_resource::<Windows>()
+            .insert_resource(CursorControl::new(MockCursor { locked: false }))
+            .add_system(camera_zoom);
+        let free = app
+            .world
+            .spawn()
+            .insert(PerspectiveProjection::default())
+            .insert(FpsCam::default())
+            .id();
+        let driven = app
+            .world
+            .spawn()
+            .insert(PerspectiveProjection::default())
+            .insert(FpsCam::default())
+            .insert(MoveTo::new(Transform::default(), 10.0))
+            .id();
+        let zoomed = |app: &App, camera| app.world.get::<Zoomed>(camera).is_some();
+
+        press_key(&mut app, KeyCode::Z);
+        app.update();
+        assert!(!zoomed(&app, free) && !zoomed(&app, driven));
+
+        set_cursor_locked(&mut app, true);
+        app.update();
+        assert!(zoomed(&app, free));
+        assert!(!zoomed(&app, driven));
+    }
+
+    #[test]
+    fn look_momentum_keeps_turning_then_settles() {
+        let (mut app, camera) = look_app(Config {
+            look_friction: Some(5.0),
+            ..Default::default()
+        });
+
+        look(&mut app, Vec2::new(100.0, 0.0));
+        let mut yaw = yaw_pitch(&app, camera).0;
+        for _ in 0..3 {
+            app.update();
+            let next = yaw_pitch(&app, camera).0;
+            // still turning the same way, without any mouse motion
+            assert!(next < yaw, "{} didn't keep turning from {}", next, yaw);
+            yaw = next;
+        }
+
+        for _ in 0..50 {
+            app.update();
+        }
+        let settled = yaw_pitch(&app, camera);
+        app.update();
+        assert_eq!(yaw_pitch(&app, camera), settled);
+    }
+
+    #[test]
+    fn frustum_corners_of_a_known_pose() {
+        // with a 90° fov the half height at distance 1 is 1, and twice that
+        // wide with an aspect ratio of 2
+        let corners = frustum_corners(&Transform::default(), &projection(), 1.0, 10.0);
+        let expected = [
+            Vec3::new(-2.0, -1.0, -1.0),
+            Vec3::new(2.0, -1.0, -1.0),
+            Vec3::new(2.0, 1.0, -1.0),
+            Vec3::new(-2.0, 1.0, -1.0),
+            Vec3::new(-20.0, -10.0, -10.0),
+            Vec3::new(20.0, -10.0, -10.0),
+            Vec3::new(20.0, 10.0, -10.0),
+            Vec3::new(-20.0, 10.0, -10.0),
+        ];
+        for (corner, expected) in corners.into_iter().zip(expected) {
+            assert_near(corner, expected);
+        }
+
+        // turned around to face +Z from (0, 0, 5)
+        let transform = Transform {
+            translation: Vec3::new(0.0, 0.0, 5.0),
+            rotation: Quat::from_rotation_y(std::f32::consts::PI),
+            ..Default::default()
+        };
+        let corners = frustum_corners(&transform, &projection(), 1.0, 10.0);
+        assert_near(corners[0], Vec3::new(2.0, -1.0, 6.0));
+        assert_near(corners[6], Vec3::new(-20.0, 10.0, 15.0));
+    }
+
+    /// The yaws a camera with `config` ends up at while the mouse is swept
+    /// all the way to one side and back past the other
+    fn sweep_yaw(config: &Config) -> Vec<f32> {
+        let mut transform = Transform::default();
+        let mut fpscam = FpsCam::default();
+        let sweep = [0.2; 40].into_iter().chain([-0.2; 80]);
+        sweep
+            .map(|yaw| {
+                turn(&mut transform, &mut fpscam, config, yaw, 0.0);
+                fpscam.yaw
+            })
+            .collect()
+    }
+
+    #[test]
+    fn yaw_limits_hold_while_sweeping() {
+        let config = Config {
+            yaw_limits: Some((-0.5, 0.3)),
+            ..Default::default()
+        };
+
+        let yaws = sweep_yaw(&config);
+        assert!(yaws.iter().all(|yaw| (-0.5..=0.3).contains(yaw)));
+        assert_eq!(yaws[39], 0.3);
+        assert_eq!(*yaws.last().unwrap(), -0.5);
+    }
+
+    #[test]
+    fn yaw_limits_can_wrap_around_the_back() {
+        use std::f32::consts::PI;
+
+        let config = Config {
+            yaw_limits: Some((3.0, -3.0)),
+            ..Default::default()
+        };
+        let in_arc = |yaw: f32| yaw >= 3.0 - 1e-5 || yaw <= -3.0 + 1e-5;
+
+        // starting outside of the arc snaps to its nearest end
+        assert_eq!(clamp_yaw(0.1, &config), 3.0);
+        assert_eq!(clamp_yaw(-0.1, &config), -3.0);
+        // crossing the back of the camera is allowed
+        assert!((clamp_yaw(PI + 0.1, &config) - (-PI + 0.1)).abs() < 1e-5);
+
+        let mut transform = Transform::default();
+        let mut fpscam = FpsCam {
+            yaw: PI,
+            pitch: 0.0,
+        };
+        for yaw in [0.05; 10].into_iter().chain([-0.05; 20]) {
+            turn(&mut transform, &mut fpscam, &config, yaw, 0.0);
+            assert!(in_arc(fpscam.yaw), "yaw {} outside of the arc", fpscam.yaw);
+        }
+    }
+
+    #[test]
+    fn inverted_yaw_limits_do_not_panic() {
+        let config = Config {
+            yaw_limits: Some((0.5, -0.5)),
+            ..Default::default()
+        };
+        assert!(sweep_yaw(&config).iter().all(|yaw| yaw.is_finite()));
+    }
+
+    #[test]
+    fn dpi_scale_is_relative_to_a_1080_pixel_window() {
+        assert_eq!(dpi_scale(1080), 1.0);
+        assert_eq!(dpi_scale(2160), 0.5);
+        assert_eq!(dpi_scale(720), 1.5);
+        assert_eq!(dpi_scale(0), 1.0);
+
+        // the same fraction of the window turns the camera the same amount
+        let scaled = |height: u32, pixels: f32| pixels * dpi_scale(height);
+        assert_eq!(scaled(1080, 108.0), scaled(2160, 216.0));
+    }
+
+    /// A `CursorController` which rejects the first `rejections` requests to
+    /// lock the cursor, like a browser might
+    struct RejectingCursor {
+        rejections: u32,
+        locked: bool,
+    }
+
+    impl CursorController for RejectingCursor {
+        fn lock(&mut self, _: &mut Windows) {
+            if self.rejections > 0 {
+                self.rejections -= 1;
+            } else {
+                self.locked = true;
+            }
+        }
+
+        fn unlock(&mut self, _: &mut Windows) {
+            self.locked = false;
+        }
+
+        fn is_locked(&self, _: &Windows) -> bool {
+            self.locked
+        }
+    }
+
+    fn click(app: &mut App) {
+        let mut events = app
+            .world
+            .get_resource_mut::<Events<MouseButtonInput>>()
+            .unwrap();
+        events.send(MouseButtonInput {
+            button: MouseButton::Left,
+            state: ElementState::Pressed,
+        });
+    }
+
+    fn lock_status(app: &App) -> CursorLockStatus {
+        *app.world.get_resource::<CursorLockStatus>().unwrap()
+    }
+
+    #[test]
+    fn rejected_cursor_locks_fail_and_are_retried() {
+        let mut app = App::new();
         app.init_resource::<Config>()
-            .add_system(camera_move)
-            .add_system(camera_look)
-            .add_system(lock_on_focus)
-            .add_system(lock_cursor)
-            .add_system(unlock_cursor);
+            .init_resource::<UnlockTriggers>()
+            .init_resource::<Windows>()
+            .insert_resource(CursorControl::new(RejectingCursor {
+                rejections: 1,
+                locked: false,
+            }))
+            .init_resource::<CursorLockStatus>()
+            .add_event::<MouseButtonInput>()
+            .add_system(lock_cursor);
+        app.world.spawn().insert(FpsCam::default());
+
+        click(&mut app);
+        app.update();
+        assert_eq!(lock_status(&app), CursorLockStatus::Pending);
+
+        for _ in 0..LOCK_REQUEST_FRAMES {
+            app.update();
+        }
+        assert_eq!(lock_status(&app), CursorLockStatus::Failed);
+        assert!(!cursor_locked(&app));
+
+        click(&mut app);
+        app.update();
+        assert_eq!(lock_status(&app), CursorLockStatus::Idle);
+        assert!(cursor_locked(&app));
+    }
+
+    #[test]
+    fn min_movespeed_floors_the_scaled_speed() {
+        let config = Config {
+            focus_point: Some(Vec3::new(0.0, 0.0, -1e-3)),
+            min_movespeed: 0.2,
+            ..Default::default()
+        };
+
+        // right next to the focus point the distance scaling is almost zero
+        assert_eq!(movement_speed(&Transform::default(), &[], &config), 0.2);
+        assert_near(moved_by(&[KeyCode::W], &config), -Vec3::Z * 0.2);
+
+        // further away the floor no longer applies
+        let far = Transform::from_xyz(0.0, 0.0, 5.0);
+        assert!((movement_speed(&far, &[], &config) - 5.001).abs() < 1e-4);
+
+        let without_floor = Config {
+            min_movespeed: 0.0,
+            ..config
+        };
+        assert!(movement_speed(&Transform::default(), &[], &without_floor) < 1e-2);
+    }
+
+    #[test]
+    fn auto_level_leaves_held_roll_alone() {
+        let config = Config {
+            auto_level_speed: 10.0,
+            ..Config::spectator()
+        };
+
+        let mut app = App::new();
+        app.insert_resource(config)
+            .init_resource::<Input<KeyCode>>()
+            .insert_resource(frame_time())
+            .init_resource::<Windows>()
+            .insert_resource(CursorControl::new(MockCursor { locked: true }))
+            .add_system(auto_level);
+        let rolled = Quat::from_rotation_z(0.5);
+        let camera = app
+            .world
+            .spawn()
+            .insert(Transform::from_rotation(rolled))
+            .insert(FpsCam::default())
+            .id();
+        let rotation = |app: &App| app.world.get::<Transform>(camera).unwrap().rotation;
+
+        press_key(&mut app, KeyCode::Q);
+        app.update();
+        assert_eq!(rotation(&app), rolled);
+
+        app.world
+            .get_resource_mut::<Input<KeyCode>>()
+            .unwrap()
+            .release(KeyCode::Q);
+        app.update();
+        assert!(rotation(&app).angle_between(Quat::IDENTITY) < 1e-4);
+    }
+
+    #[test]
+    fn auto_level_keeps_the_heading_of_a_pitched_camera() {
+        let config = Config {
+            auto_level_speed: 10.0,
+            ..Config::spectator()
+        };
+
+        let mut app = App::new();
+        app.insert_resource(config)
+            .init_resource::<Input<KeyCode>>()
+            .insert_resource(frame_time())
+            .init_resource::<Windows>()
+            .insert_resource(CursorControl::new(MockCursor { locked: true }))
+            .add_system(auto_level);
+        let rolled = look_rotation(0.5, 0.7) * Quat::from_rotation_z(0.5);
+        let camera = app
+            .world
+            .spawn()
+            .insert(Transform::from_rotation(rolled))
+            .insert(FpsCam::default())
+            .id();
+
+        app.update();
+        let rotation = app.world.get::<Transform>(camera).unwrap().rotation;
+        assert_near(rotation * -Vec3::Z, rolled * -Vec3::Z);
+        assert!((rotation * Vec3::X).y.abs() < 1e-4, "still rolled");
+    }
+
+    #[test]
+    fn levelling_keeps_the_heading_of_a_pitched_camera() {
+        let mut app = App::new();
+        app.insert_resource(Config::spectator())
+            .init_resource::<Input<KeyCode>>()
+            .insert_resource(frame_time())
+            .init_resource::<Windows>()
+            .insert_resource(CursorControl::new(MockCursor { locked: true }))
+            .insert_resource(PendingPresses(vec![KeyCode::R]))
+            .add_system(camera_roll);
+        let rolled = look_rotation(0.5, 0.7) * Quat::from_rotation_z(0.5);
+        let camera = app
+            .world
+            .spawn()
+            .insert(Transform::from_rotation(rolled))
+            .insert(FpsCam::default())
+            .id();
+
+        app.update();
+        let target = app.world.get::<MoveTo>(camera).unwrap().target.rotation;
+        assert_near(target * -Vec3::Z, rolled * -Vec3::Z);
+        assert!((target * Vec3::X).y.abs() < 1e-4, "still rolled");
+    }
+
+    #[test]
+    fn leaving_six_dof_keeps_the_view() {
+        let six_dof = Config::spectator();
+        let rolled = look_rotation(0.5, 0.7) * Quat::from_rotation_z(0.5);
+        let mut transform = Transform::from_rotation(rolled);
+        let mut fpscam = FpsCam::default();
+
+        turn(&mut transform, &mut fpscam, &six_dof, 0.0, 0.0);
+        assert!((fpscam.yaw - 0.5).abs() < 1e-4, "yaw {}", fpscam.yaw);
+        assert!((fpscam.pitch - 0.7).abs() < 1e-4, "pitch {}", fpscam.pitch);
+
+        let config = Config {
+            six_dof: false,
+            ..six_dof
+        };
+        turn(&mut transform, &mut fpscam, &config, 0.0, 0.0);
+        assert_near(transform.rotation * -Vec3::Z, rolled * -Vec3::Z);
+    }
+
+    #[test]
+    fn look_input_events_turn_the_camera() {
+        let (mut app, camera) = look_app(Config {
+            use_mouse_motion: false,
+            ..Default::default()
+        });
+        app.add_event::<LookInput>()
+            .add_system_to_stage(CoreStage::PreUpdate, read_motion::<LookInput>);
+
+        app.world
+            .get_resource_mut::<Events<LookInput>>()
+            .unwrap()
+            .send(LookInput(Vec2::new(100.0, 50.0)));
+        app.update();
+
+        let (yaw, pitch) = yaw_pitch(&app, camera);
+        assert!((yaw + 0.1).abs() < 1e-6, "yaw {}", yaw);
+        assert!((pitch + 0.05).abs() < 1e-6, "pitch {}", pitch);
+    }
+
+    #[test]
+    fn fpscam_state_round_trips() {
+        let mut world = World::new();
+        let transform = Transform {
+            translation: Vec3::new(1.0, 2.0, 3.0),
+            rotation: look_rotation(0.4, -0.2),
+            ..Default::default()
+        };
+        let camera = world
+            .spawn()
+            .insert(transform)
+            .insert(FpsCam {
+                yaw: 0.4,
+                pitch: -0.2,
+            })
+            .insert(MovementState {
+                smoothed_velocity: Vec3::X,
+                speed: Some(2.0),
+                ..Default::default()
+            })
+            .insert(LookState::default())
+            .id();
+
+        let state = FpsCamState::capture(&world, camera).unwrap();
+        assert_eq!(state.transform, transform);
+        assert_eq!((state.yaw, state.pitch), (0.4, -0.2));
+        assert_eq!(state.smoothed_velocity, Vec3::X);
+        assert_eq!(state.speed, Some(2.0));
+
+        // move the camera somewhere else, with motion still waiting to apply
+        let mut fpscam = FpsCam::default();
+        let mut moved = transform;
+        fpscam.set_pose(&mut moved, Vec3::ZERO, 2.0, 1.0, &Config::default());
+        *world.get_mut::<Transform>(camera).unwrap() = moved;
+        *world.get_mut::<FpsCam>(camera).unwrap() = fpscam;
+        world.get_mut::<LookState>(camera).unwrap().pending = Vec2::ONE;
+        {
+            let mut movement = world.get_mut::<MovementState>(camera).unwrap();
+            movement.smoothed_velocity = Vec3::Z * 5.0;
+            movement.speed = None;
+        }
+
+        state.restore(&mut world, camera);
+        assert_eq!(FpsCamState::capture(&world, camera), Some(state));
+        assert_eq!(world.get::<LookState>(camera).unwrap().pending, Vec2::ZERO);
+        // the restored transform matches its yaw and pitch, so it won't snap
+        let fpscam = world.get::<FpsCam>(camera).unwrap();
+        let rotation = look_rotation(fpscam.yaw, fpscam.pitch);
+        assert!(rotation.angle_between(transform.rotation) < 1e-5);
+    }
+
+    #[test]
+    fn fpscam_state_round_trips_without_the_eye_offset() {
+        let mut app = App::new();
+        app.insert_resource(Config::default())
+            .insert_resource(frame_time())
+            .add_system_to_stage(CoreStage::PreUpdate, reset_camera_state)
+            .add_system(apply_view_offset);
+        let camera = app
+            .world
+            .spawn()
+            .insert(Transform::default())
+            .insert(FpsCam::default())
+            .insert(ViewOffset::default())
+            .insert(LookState::default())
+            .insert(MovementState::default())
+            .insert(EyeOffset(Vec3::new(0.0, 1.7, 0.0)))
+            .id();
+        let height = |app: &App| app.world.get::<Transform>(camera).unwrap().translation.y;
+
+        app.update();
+        assert!((height(&app) - 1.7).abs() < 1e-5, "height {}", height(&app));
+
+        for _ in 0..3 {
+            let state = FpsCamState::capture(&app.world, camera).unwrap();
+            assert_near(state.transform.translation, Vec3::ZERO);
+            state.restore(&mut app.world, camera);
+            app.update();
+            assert!((height(&app) - 1.7).abs() < 1e-5, "height {}", height(&app));
+        }
+    }
+
+    #[test]
+    fn capturing_a_non_camera_fails() {
+        let mut world = World::new();
+        let entity = world.spawn().insert(Transform::default()).id();
+        assert_eq!(FpsCamState::capture(&world, entity), None);
+    }
+
+    #[test]
+    fn toggles_are_handled_once_however_many_steps_run() {
+        use bevy::ecs::schedule::Stage;
+
+        let mut world = World::new();
+        world.insert_resource(Config {
+            key_bindings: KeyBindings {
+                lock_yaw: Some(KeyCode::Y.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        world.insert_resource(Input::<KeyCode>::default());
+        world.insert_resource(Windows::default());
+        world.insert_resource(CursorControl::new(MockCursor { locked: true }));
+        world.insert_resource(PendingPresses::default());
+        world.insert_resource(LookAxisLocks::default());
+
+        // what runs every frame, and what runs at every step of the cameras
+        let mut frame = SystemStage::single_threaded().with_system(latch_presses);
+        let mut step = SystemStage::single_threaded()
+            .with_system(toggle_look_locks.label("toggle"))
+            .with_system(consume_presses.after("toggle"));
+        let press = |world: &mut World| {
+            let mut keys = world.get_resource_mut::<Input<KeyCode>>().unwrap();
+            keys.release(KeyCode::Y);
+            keys.clear();
+            keys.press(KeyCode::Y);
+        };
+        let end_frame = |world: &mut World| {
+            world.get_resource_mut::<Input<KeyCode>>().unwrap().clear();
+        };
+        let yaw_locked = |world: &World| world.get_resource::<LookAxisLocks>().unwrap().yaw;
+
+        // a frame with two steps toggles only once
+        press(&mut world);
+        frame.run(&mut world);
+        step.run(&mut world);
+        step.run(&mut world);
+        end_frame(&mut world);
+        assert!(yaw_locked(&world));
+
+        // a press in a frame without a step is handled by the next step
+        press(&mut world);
+        frame.run(&mut world);
+        end_frame(&mut world);
+        assert!(yaw_locked(&world));
+        frame.run(&mut world);
+        step.run(&mut world);
+        end_frame(&mut world);
+        assert!(!yaw_locked(&world));
+    }
+
+    #[test]
+    fn reduced_motion_disables_smoothing() {
+        let config = Config {
+            reduced_motion: true,
+            look_smoothing: 0.2,
+            velocity_smoothing: 0.3,
+            speed_smoothing: 0.4,
+            head_bob_enabled: true,
+            ..Default::default()
+        }
+        .with_reduced_motion();
+
+        assert_eq!(config.look_smoothing, 0.0);
+        assert_eq!(config.velocity_smoothing, 0.0);
+        assert_eq!(config.speed_smoothing, 0.0);
+        assert!(!config.head_bob_enabled);
+        assert_eq!(config.shake_max_angle, 0.0);
+    }
+
+    #[test]
+    fn reduced_motion_overrides_the_smoothing_of_profiles() {
+        let (mut app, camera) = move_app(Config {
+            reduced_motion: true,
+            ..Default::default()
+        });
+        {
+            let mut profiles = app.world.get_resource_mut::<MovementProfiles>().unwrap();
+            profiles.insert(
+                "smooth",
+                MovementProfile {
+                    velocity_smoothing: 0.5,
+                    ..Default::default()
+                },
+            );
+            profiles.set_active(Some("smooth"));
+        }
+
+        // without smoothing the camera starts moving at full speed right away
+        press_key(&mut app, KeyCode::W);
+        assert_near(step(&mut app, camera), -Vec3::Z * FRAME);
+    }
+
+    #[test]
+    fn locked_axes_ignore_mouse_motion() {
+        let (mut app, camera) = look_app(Config::default());
+        app.insert_resource(LookAxisLocks {
+            pitch: false,
+            yaw: true,
+        });
+        look(&mut app, Vec2::new(100.0, 50.0));
+        let (yaw, pitch) = yaw_pitch(&app, camera);
+        assert_eq!(yaw, 0.0);
+        assert!((pitch + 0.05).abs() < 1e-6, "pitch {}", pitch);
+
+        let (mut app, camera) = look_app(Config::default());
+        app.insert_resource(LookAxisLocks {
+            pitch: true,
+            yaw: false,
+        });
+        look(&mut app, Vec2::new(100.0, 50.0));
+        let (yaw, pitch) = yaw_pitch(&app, camera);
+        assert!((yaw + 0.1).abs() < 1e-6, "yaw {}", yaw);
+        assert_eq!(pitch, 0.0);
+    }
+
+    fn direction_of(held: &[KeyCode], config: &Config, transform: &Transform) -> Vec3 {
+        let mut keys = Input::<KeyCode>::default();
+        for &key in held {
+            keys.press(key);
+        }
+        movement_direction(&keys, config, transform)
+    }
+
+    #[test]
+    fn movement_direction_of_each_key() {
+        let config = Config::default();
+        let transform = Transform::default();
+
+        let expected = [-Vec3::Z, Vec3::Z, -Vec3::X, Vec3::X, Vec3::Y, -Vec3::Y];
+        for ((_, key), expected) in MOVEMENT_ACTIONS.into_iter().zip(expected) {
+            assert_near(direction_of(&[key], &config, &transform), expected);
+        }
+        assert_near(direction_of(&[], &config, &transform), Vec3::ZERO);
+    }
+
+    #[test]
+    fn movement_direction_of_combinations() {
+        let config = Config::default();
+
+        // turned to face -X, so forward and right are rotated along
+        let transform = Transform::from_rotation(look_rotation(std::f32::consts::FRAC_PI_2, 0.0));
+        assert_near(direction_of(&[KeyCode::W], &config, &transform), -Vec3::X);
+        assert_near(
+            direction_of(&[KeyCode::W, KeyCode::D], &config, &transform),
+            Vec3::new(-1.0, 0.0, -1.0).normalize(),
+        );
+        assert_near(
+            direction_of(
+                &[KeyCode::S, KeyCode::A, KeyCode::Space],
+                &config,
+                &transform,
+            ),
+            Vec3::new(1.0, 1.0, 1.0).normalize(),
+        );
+        assert_near(
+            direction_of(&[KeyCode::A, KeyCode::D], &config, &transform),
+            Vec3::ZERO,
+        );
+    }
+
+    #[test]
+    fn vertical_movespeed_unset_moves_at_one_speed() {
+        let config = Config {
+            movespeed: 2.0,
+            ..Default::default()
+        };
+
+        assert_near(moved_by(&[KeyCode::Space], &config), Vec3::Y * 2.0);
+        assert_near(
+            moved_by(&[KeyCode::W, KeyCode::Space], &config),
+            Vec3::new(0.0, 1.0, -1.0).normalize() * 2.0,
+        );
+    }
+
+    #[test]
+    fn vertical_movespeed_moves_up_and_down_at_its_own_speed() {
+        let config = Config {
+            movespeed: 2.0,
+            vertical_movespeed: Some(3.0),
+            key_bindings: KeyBindings {
+                sprint: Some(KeyCode::LShift.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_near(moved_by(&[KeyCode::Space], &config), Vec3::Y * 3.0);
+        assert_near(moved_by(&[KeyCode::LControl], &config), -Vec3::Y * 3.0);
+        assert_near(moved_by(&[KeyCode::W], &config), -Vec3::Z * 2.0);
+        // the vertical part isn't normalized together with the horizontal one
+        assert_near(
+            moved_by(&[KeyCode::W, KeyCode::Space], &config),
+            Vec3::new(0.0, 3.0, -2.0),
+        );
+        // sprinting scales both speeds alike
+        assert_near(
+            moved_by(&[KeyCode::W, KeyCode::Space, KeyCode::LShift], &config),
+            Vec3::new(0.0, 6.0, -4.0),
+        );
+    }
+
+    #[test]
+    fn vertical_movespeed_ignores_the_focus_point_and_floor() {
+        let config = Config {
+            movespeed: 2.0,
+            vertical_movespeed: Some(3.0),
+            focus_point: Some(Vec3::new(0.0, 0.0, -5.0)),
+            min_movespeed: 20.0,
+            key_bindings: KeyBindings {
+                sprint: Some(KeyCode::LShift.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // the horizontal speed is scaled by the distance and then floored
+        assert_near(moved_by(&[KeyCode::W], &config), -Vec3::Z * 20.0);
+        assert_near(moved_by(&[KeyCode::Space], &config), Vec3::Y * 3.0);
+        assert_near(
+            moved_by(&[KeyCode::Space, KeyCode::LShift], &config),
+            Vec3::Y * 3.0 * config.sprint_multiplier,
+        );
+
+        let unfloored = Config {
+            min_movespeed: 0.0,
+            ..config
+        };
+        assert_near(moved_by(&[KeyCode::W], &unfloored), -Vec3::Z * 10.0);
+        assert_near(moved_by(&[KeyCode::Space], &unfloored), Vec3::Y * 3.0);
+    }
+
+    #[test]
+    fn start_cursor_is_left_alone_without_cursor_cameras() {
+        for manage in [false, true] {
+            let mut app = App::new();
+            app.insert_resource(Config::default())
+                .init_resource::<Windows>()
+                .insert_resource(CursorControl::new(MockCursor { locked: false }))
+                .add_startup_system_to_stage(StartupStage::PostStartup, apply_start_cursor);
+            let camera = app.world.spawn().insert(FpsCam::default()).id();
+            if !manage {
+                app.world.entity_mut(camera).insert(NoCursorControl);
+            }
+
+            app.update();
+            assert_eq!(cursor_locked(&app), manage);
+        }
+    }
+
+    #[test]
+    fn the_first_spawned_camera_becomes_active() {
+        let mut app = App::new();
+        app.insert_resource(Config {
+            control_all_cameras: false,
+            ..Default::default()
+        })
+        .add_system(ensure_active_camera);
+        let is_active = |app: &App, camera| app.world.get::<ActiveFpsCam>(camera).is_some();
+
+        // free two ids, so that the second camera reuses a lower one than
+        // the first camera
+        let a = app.world.spawn().id();
+        let b = app.world.spawn().id();
+        app.world.despawn(b);
+        let first = app.world.spawn().insert(FpsCam::default()).id();
+        app.world.despawn(a);
+        app.update();
+        assert!(is_active(&app, first));
+
+        app.world.entity_mut(first).remove::<ActiveFpsCam>();
+        let second = app.world.spawn().insert(FpsCam::default()).id();
+        assert!(second.id() < first.id());
+        app.update();
+        assert!(is_active(&app, first));
+        assert!(!is_active(&app, second));
+    }
+
+    #[test]
+    fn gamepad_movement_uses_the_profile_and_fast_movement() {
+        let gamepad = Gamepad(0);
+        let mut axes = Axis::<GamepadAxis>::default();
+        axes.set(GamepadAxis(gamepad, GamepadAxisType::LeftStickY), 1.0);
+        let mut profiles = MovementProfiles::default();
+        profiles.insert(
+            "slow",
+            MovementProfile {
+                movespeed: 0.5,
+                ..Default::default()
+            },
+        );
+        profiles.set_active(Some("slow"));
+
+        let mut app = App::new();
+        app.insert_resource(Config::default())
+            .insert_resource(frame_time())
+            .insert_resource(axes)
+            .init_resource::<Input<GamepadButton>>()
+            .init_resource::<FastMovement>()
+            .insert_resource(profiles)
+            .add_event::<FpsCamMoved>()
+            .add_event::<FpsCamLooked>()
+            .add_event::<BoundsHit>()
+            .add_event::<HighSpeed>()
+            .add_system(camera_gamepad);
+        let camera = app
+            .world
+            .spawn()
+            .insert(Transform::default())
+            .insert(FpsCam::default())
+            .insert(MovementState::default())
+            .insert(PlayerInput::Gamepad(gamepad))
+            .id();
+        let position = |app: &App| app.world.get::<Transform>(camera).unwrap().translation;
+
+        app.update();
+        assert_near(position(&app), -Vec3::Z * 0.5 * FRAME);
+
+        app.insert_resource(FastMovement(true));
+        app.update();
+        let fast = Config::default().fast_movespeed;
+        assert_near(position(&app), -Vec3::Z * (0.5 + fast) * FRAME);
+    }
+
+    #[test]
+    fn asymmetric_pitch_limits_hold_at_both_ends() {
+        let config = Config::ground();
+        let mut transform = Transform::default();
+        let mut fpscam = FpsCam {
+            yaw: 0.7,
+            pitch: 0.0,
+        };
+
+        for (pitch, limit) in [(0.1, 1.4), (-0.1, -0.2)] {
+            for _ in 0..30 {
+                turn(&mut transform, &mut fpscam, &config, 0.0, pitch);
+                assert!((-0.2..=1.4).contains(&fpscam.pitch));
+            }
+            assert_eq!(fpscam.pitch, limit);
+
+            // the rotation at the limit looks exactly that far up or down,
+            // keeps the yaw and doesn't roll
+            let forward = transform.forward();
+            assert!((forward.y - limit.sin()).abs() < 1e-5);
+            assert!(transform.right().y.abs() < 1e-5);
+            let (yaw, pitch) = yaw_pitch_from_rotation(transform.rotation);
+            assert!((yaw - 0.7).abs() < 1e-5 && (pitch - limit).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn pitch_limits_stay_within_straight_up_and_down() {
+        let config = Config {
+            pitch_min: -3.0,
+            pitch_max: 3.0,
+            ..Default::default()
+        };
+        assert_eq!(limit_pitch(2.0, &config), std::f32::consts::FRAC_PI_2);
+        assert_eq!(limit_pitch(-2.0, &config), -std::f32::consts::FRAC_PI_2);
+
+        let inverted = Config {
+            pitch_min: 0.6,
+            pitch_max: 0.2,
+            ..Default::default()
+        };
+        assert!((limit_pitch(-1.0, &inverted) - 0.4).abs() < 1e-6);
     }
 }